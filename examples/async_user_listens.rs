@@ -0,0 +1,21 @@
+use listenbrainz::raw::{AsyncClient, UserName};
+
+#[tokio::main]
+async fn main() {
+    let user_name: UserName = std::env::args()
+        .nth(1)
+        .expect("No username provided")
+        .parse()
+        .expect("Invalid username");
+
+    let client = AsyncClient::new();
+
+    let result = client.user_listen_count(&user_name).await;
+    println!("Listen count: {:#?}", result);
+
+    let result = client.user_playing_now(&user_name).await;
+    println!("Playing now: {:#?}", result);
+
+    let result = client.user_listens(&user_name, None, None, Some(5)).await;
+    println!("Recent listens: {:#?}", result);
+}
@@ -1,10 +1,14 @@
-use listenbrainz::raw::Client;
+use listenbrainz::raw::{Client, Mbid};
 
 fn main() {
     let client = Client::new();
-    let playlist = std::env::args().nth(1).expect("No playlist provided");
+    let playlist: Mbid = std::env::args()
+        .nth(1)
+        .expect("No playlist provided")
+        .parse()
+        .expect("Invalid playlist MBID");
 
     // Example playlist: f316bb0e-8e26-44f8-a802-6d2a3688fc7d
-    let playlist = client.get_playlist(&playlist).unwrap();
+    let playlist = client.get_playlist(None, &playlist).unwrap();
     println!("Playlist: {:#?}", playlist)
 }
@@ -3,7 +3,7 @@ use listenbrainz::raw::Client;
 fn main() {
     let token = std::env::args().nth(1).expect("No token provided");
 
-    let client = Client::new();
+    let mut client = Client::new();
 
     loop {
         match client.validate_token(&token) {
@@ -24,4 +24,14 @@ fn main() {
             }
         }
     }
+
+    // With throttling enabled, the client waits for the rate limit to reset instead
+    client.set_throttling(true);
+
+    for _ in 0..5 {
+        match client.validate_token(&token) {
+            Ok(response) => println!("{:?}", response.rate_limit),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
 }
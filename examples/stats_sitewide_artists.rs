@@ -1,8 +1,9 @@
+use listenbrainz::raw::request::StatsRange;
 use listenbrainz::raw::Client;
 
 fn main() {
     let client = Client::new();
 
-    let result = client.stats_sitewide_artists(None, None, Some("year"));
+    let result = client.stats_sitewide_artists(None, None, Some(StatsRange::Year));
     println!("{:#?}", result);
 }
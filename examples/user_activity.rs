@@ -1,7 +1,11 @@
-use listenbrainz::raw::Client;
+use listenbrainz::raw::{Client, UserName};
 
 fn main() {
-    let user_name = std::env::args().nth(1).expect("No username provided");
+    let user_name: UserName = std::env::args()
+        .nth(1)
+        .expect("No username provided")
+        .parse()
+        .expect("Invalid username");
 
     let client = Client::new();
 
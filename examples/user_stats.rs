@@ -1,9 +1,13 @@
-use listenbrainz::raw::Client;
+use listenbrainz::raw::{Client, UserName};
 
 fn main() {
     let mut args = std::env::args().skip(1);
     let subject = args.next().expect("No subject provided");
-    let user_name = args.next().expect("No username provided");
+    let user_name: UserName = args
+        .next()
+        .expect("No username provided")
+        .parse()
+        .expect("Invalid username");
 
     let client = Client::new();
 
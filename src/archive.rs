@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Write as _};
+use std::path::Path;
+
+use crate::error::Error;
+use crate::raw::Mbid;
+
+/// A record of the playlists that have been archived, mapping the MBID of every source
+/// playlist to the MBID of its copy.
+///
+/// Used by [`ListenBrainz::archive_created_for_playlists`](crate::ListenBrainz::archive_created_for_playlists)
+/// to copy every playlist only once. Save it to a file between runs with
+/// [`PlaylistArchive::save`], and load it again with [`PlaylistArchive::load`].
+///
+/// The file is a plain text file with a line per playlist, containing the MBID of the source
+/// playlist and the MBID of the copy, separated by a tab.
+///
+/// # Example
+///
+/// ```no_run
+/// # use listenbrainz::{ListenBrainz, PlaylistArchive};
+/// let mut client = ListenBrainz::new();
+/// client.authenticate("LISTENBRAINZ TOKEN")?;
+///
+/// let mut archive = PlaylistArchive::load("archive.tsv")?;
+/// let result = client.archive_created_for_playlists(&mut archive);
+/// archive.save("archive.tsv")?;
+///
+/// for playlist in result? {
+///     println!("archived {:?} as {}", playlist.title, playlist.copy_mbid);
+/// }
+/// # Ok::<(), listenbrainz::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlaylistArchive {
+    playlists: BTreeMap<String, String>,
+}
+
+impl PlaylistArchive {
+    /// Construct an empty archive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load an archive from a file, or construct an empty archive if the file does not exist.
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read or contains a line that is not valid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(error) => return Err(Error::Io(error)),
+        };
+
+        let mut archive = Self::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let parsed = line
+                .split_once('\t')
+                .filter(|(source, copy)| {
+                    Mbid::validate(source).is_ok() && Mbid::validate(copy).is_ok()
+                })
+                .ok_or_else(|| {
+                    let message = format!("invalid playlist archive line: {line:?}");
+                    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+                })?;
+            archive.insert(parsed.0, parsed.1);
+        }
+
+        Ok(archive)
+    }
+
+    /// Save the archive to a file, replacing its contents.
+    ///
+    /// The archive is written to a temporary file in the same directory first, which then
+    /// replaces the file, so that the file is never left half-written.
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut contents = String::new();
+        for (source, copy) in &self.playlists {
+            writeln!(contents, "{source}\t{copy}").unwrap();
+        }
+
+        let file_name = path.file_name().ok_or_else(|| {
+            let message = format!("invalid playlist archive path: {path:?}");
+            Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message))
+        })?;
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        let result = write_synced(&temp_path, contents.as_bytes())
+            .and_then(|()| fs::rename(&temp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result.map_err(Error::Io)
+    }
+
+    /// Check if the playlist with the given MBID has been archived.
+    pub fn contains(&self, source_mbid: &str) -> bool {
+        self.playlists.contains_key(&source_mbid.to_lowercase())
+    }
+
+    /// Get the MBID of the copy of the playlist with the given MBID, if it has been archived.
+    pub fn get(&self, source_mbid: &str) -> Option<&str> {
+        self.playlists
+            .get(&source_mbid.to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Record that a playlist has been archived as the playlist with MBID `copy_mbid`.
+    pub fn insert(&mut self, source_mbid: &str, copy_mbid: &str) {
+        self.playlists
+            .insert(source_mbid.to_lowercase(), copy_mbid.to_lowercase());
+    }
+
+    /// Iterate over the MBIDs of the archived playlists and their copies.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.playlists
+            .iter()
+            .map(|(source, copy)| (source.as_str(), copy.as_str()))
+    }
+
+    /// Get the number of archived playlists.
+    pub fn len(&self) -> usize {
+        self.playlists.len()
+    }
+
+    /// Check if no playlists have been archived.
+    pub fn is_empty(&self) -> bool {
+        self.playlists.is_empty()
+    }
+}
+
+/// Write `contents` to a new file at `path`, and wait until it is stored on disk.
+fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// A playlist that was archived by
+/// [`ListenBrainz::archive_created_for_playlists`](crate::ListenBrainz::archive_created_for_playlists).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedPlaylist {
+    /// MBID of the source playlist.
+    pub source_mbid: String,
+
+    /// MBID of the copy.
+    pub copy_mbid: String,
+
+    /// Title of the source playlist.
+    pub title: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+    const COPY: &str = "f316bb0e-8e26-44f8-a802-6d2a3688fc7d";
+
+    #[test]
+    fn looks_up_playlists_case_insensitively() {
+        let mut archive = PlaylistArchive::new();
+        archive.insert(&SOURCE.to_uppercase(), &COPY.to_uppercase());
+
+        assert!(archive.contains(SOURCE));
+        assert!(archive.contains(&SOURCE.to_uppercase()));
+        assert_eq!(archive.get(&SOURCE.to_uppercase()), Some(COPY));
+        assert!(!archive.contains(COPY));
+        assert_eq!(archive.iter().collect::<Vec<_>>(), [(SOURCE, COPY)]);
+    }
+
+    #[test]
+    fn saves_and_loads_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.tsv");
+
+        let archive = PlaylistArchive::load(&path).unwrap();
+        assert!(archive.is_empty());
+
+        let mut archive = PlaylistArchive::new();
+        archive.insert(SOURCE, COPY);
+        archive.insert(COPY, SOURCE);
+        archive.save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{SOURCE}\t{COPY}\n{COPY}\t{SOURCE}\n")
+        );
+        assert_eq!(PlaylistArchive::load(&path).unwrap(), archive);
+
+        // Saving again replaces the file, and leaves no temporary file behind
+        let mut smaller = PlaylistArchive::new();
+        smaller.insert(SOURCE, COPY);
+        smaller.save(&path).unwrap();
+        assert_eq!(PlaylistArchive::load(&path).unwrap(), smaller);
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn keeps_the_file_if_saving_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.tsv");
+        fs::write(&path, format!("{SOURCE}\t{COPY}\n")).unwrap();
+
+        // The temporary file cannot be created where a directory is in the way
+        fs::create_dir(dir.path().join(".archive.tsv.tmp")).unwrap();
+        let result = PlaylistArchive::new().save(&path);
+        assert!(matches!(result, Err(Error::Io(_))));
+        assert_eq!(PlaylistArchive::load(&path).unwrap().len(), 1);
+
+        let missing = dir.path().join("missing/archive.tsv");
+        assert!(matches!(
+            PlaylistArchive::new().save(missing),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn loads_archives_with_blank_lines_and_mixed_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.tsv");
+        fs::write(
+            &path,
+            format!("\n  {}\t{COPY}  \r\n\n", SOURCE.to_uppercase()),
+        )
+        .unwrap();
+
+        let archive = PlaylistArchive::load(&path).unwrap();
+        assert_eq!(archive.get(SOURCE), Some(COPY));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.tsv");
+
+        for contents in [
+            format!("{SOURCE} {COPY}\n"),
+            format!("{SOURCE}\tnot an MBID\n"),
+            format!("{SOURCE}\t{COPY}\n{SOURCE}\n"),
+        ] {
+            fs::write(&path, contents).unwrap();
+            match PlaylistArchive::load(&path) {
+                Err(Error::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::InvalidData),
+                result => panic!("expected an invalid data error, got {result:?}"),
+            }
+        }
+    }
+}
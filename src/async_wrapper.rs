@@ -0,0 +1,630 @@
+use crate::archive::{ArchivedPlaylist, PlaylistArchive};
+use crate::error::Error;
+use crate::raw::request::{
+    ListenType, MetadataSubmitManualMapping, MusicService, Payload, PlaylistCreate, PlaylistEdit,
+    PlaylistEditPlaylist, PlaylistItemDelete, PlaylistItemMove, RecommendationFeedbackDelete,
+    RecommendationFeedbackRating, RecommendationFeedbackSubmit, SubmitListens, TrackMetadata,
+};
+use crate::raw::response::UserListensListen;
+use crate::raw::{AsyncClient, ListensPager, Mbid, OffsetPager, Page, UserName};
+use crate::wrapper::{
+    now, playlist_create_from, playlist_item_chunks, saved_playlist_mbid, split_playlist_create,
+    unarchived_playlists, Auth, PlaylistBuilder, MAX_LISTENS_PER_REQUEST,
+    MAX_RECOMMENDATIONS_PER_REQUEST,
+};
+
+/// An ergonomic asynchronous ListenBrainz client.
+///
+/// This is the asynchronous counterpart of [`ListenBrainz`](crate::ListenBrainz),
+/// built on top of [`AsyncClient`](crate::raw::AsyncClient).
+///
+/// Requires the `async` feature.
+#[derive(Debug)]
+pub struct AsyncListenBrainz {
+    client: AsyncClient,
+    auth: Option<Auth>,
+}
+
+impl AsyncListenBrainz {
+    /// Construct a new ListenBrainz client that is not authenticated.
+    pub fn new() -> Self {
+        Self {
+            client: AsyncClient::new(),
+            auth: None,
+        }
+    }
+
+    /// Construct a new ListenBrainz client with a custom API URL that is not authenticated.
+    pub fn new_with_url(url: &str) -> Self {
+        Self {
+            client: AsyncClient::new_with_url(url),
+            auth: None,
+        }
+    }
+
+    /// Get the API URL of this client.
+    pub fn api_url(&self) -> &str {
+        self.client.api_url()
+    }
+
+    /// Check if this client is authenticated.
+    pub fn is_authenticated(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    /// Return the token if authenticated or [`None`] if not.
+    pub fn authenticated_token(&self) -> Option<&str> {
+        self.auth.as_ref().map(|auth| auth.token.as_str())
+    }
+
+    /// Return the user if authenticated or [`None`] if not.
+    pub fn authenticated_user(&self) -> Option<&str> {
+        self.auth.as_ref().map(|auth| auth.user.as_str())
+    }
+
+    /// Authenticate this client with the given token.
+    /// If the token is valid, authenticates the client.
+    /// In case the client was already authenticated, the old information
+    /// is discarded and the new token will be used.
+    ///
+    /// # Errors
+    ///
+    /// If the token was invalid, returns [`Error::InvalidToken`].
+    /// If there was an error while validating the token, that error is returned.
+    /// See the Errors section of [`AsyncClient`] for more info on what errors might occur.
+    pub async fn authenticate(&mut self, token: &str) -> Result<(), Error> {
+        let result = self.client.validate_token(token).await?;
+        match result.user_name {
+            Some(user) if result.valid => {
+                self.auth.replace(Auth {
+                    token: token.to_string(),
+                    user: UserName::new(user)?,
+                });
+                Ok(())
+            }
+            _ => Err(Error::InvalidToken),
+        }
+    }
+
+    /// Helper method to submit a listen (either "single" or "playing now").
+    async fn submit_listen(
+        &self,
+        listen_type: ListenType,
+        timestamp: Option<i64>,
+        artist: &str,
+        track: &str,
+        release: Option<&str>,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let payload = Payload {
+            listened_at: timestamp,
+            track_metadata: TrackMetadata {
+                artist_name: artist,
+                track_name: track,
+                release_name: release,
+                additional_info: None,
+            },
+        };
+
+        self.client
+            .submit_listens(
+                token,
+                SubmitListens {
+                    listen_type,
+                    payload: &[payload],
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Submit a listened track with the current time as the listen time.
+    /// This requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    /// Otherwise, see the Errors section of [`AsyncClient`] for more info on
+    /// what errors might occur.
+    pub async fn listen(
+        &self,
+        artist: &str,
+        track: &str,
+        release: Option<&str>,
+    ) -> Result<(), Error> {
+        self.submit_listen(ListenType::Single, Some(now()), artist, track, release)
+            .await
+    }
+
+    /// Submit a listened track with the given listen time, intended for importing
+    /// previously saved listens. This requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    /// Otherwise, see the Errors section of [`AsyncClient`] for more info on
+    /// what errors might occur.
+    pub async fn import(
+        &self,
+        artist: &str,
+        track: &str,
+        release: Option<&str>,
+        timestamp: i64,
+    ) -> Result<(), Error> {
+        self.submit_listen(ListenType::Import, Some(timestamp), artist, track, release)
+            .await
+    }
+
+    /// Submit a currently playing track. This requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    /// Otherwise, see the Errors section of [`AsyncClient`] for more info on
+    /// what errors might occur.
+    pub async fn playing_now(
+        &self,
+        artist: &str,
+        track: &str,
+        release: Option<&str>,
+    ) -> Result<(), Error> {
+        self.submit_listen(ListenType::PlayingNow, None, artist, track, release)
+            .await
+    }
+
+    /// Create a new playlist and return its MBID.
+    /// See [`ListenBrainz::playlist_create`](crate::ListenBrainz::playlist_create).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_create(&self, playlist: PlaylistBuilder) -> Result<String, Error> {
+        let (token, user) = self
+            .auth
+            .as_ref()
+            .map(|auth| (auth.token.as_str(), auth.user.as_str()))
+            .ok_or(Error::NotAuthenticated)?;
+
+        let create = playlist.with_default_creator(user).build()?;
+
+        self.create_playlist(token, create).await
+    }
+
+    /// Helper method to create a playlist with any number of tracks, adding the tracks
+    /// that do not fit in the `create` request afterwards, and return its MBID.
+    async fn create_playlist(
+        &self,
+        token: &str,
+        mut create: PlaylistCreate,
+    ) -> Result<String, Error> {
+        let remaining = split_playlist_create(&mut create);
+
+        let playlist_mbid = self
+            .client
+            .playlist_create(token, create)
+            .await?
+            .playlist_mbid;
+        let playlist_mbid = Mbid::new(playlist_mbid)?;
+        for add in remaining {
+            self.client
+                .playlist_item_add(token, &playlist_mbid, None, add)
+                .await?;
+        }
+
+        Ok(playlist_mbid.into())
+    }
+
+    /// Create a playlist with at most `count` of the recordings that are recommended for the
+    /// authenticated user, and return its MBID.
+    /// See [`ListenBrainz::playlist_create_from_recommendations`](crate::ListenBrainz::playlist_create_from_recommendations).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_create_from_recommendations(
+        &self,
+        playlist: PlaylistBuilder,
+        count: u64,
+    ) -> Result<Option<String>, Error> {
+        let user = self
+            .auth
+            .as_ref()
+            .map(|auth| &auth.user)
+            .ok_or(Error::NotAuthenticated)?;
+        if count == 0 {
+            return Err(Error::InvalidPlaylist(
+                "the number of recommendations must not be 0".to_string(),
+            ));
+        }
+
+        let mut pager = OffsetPager::new(Some(count.min(MAX_RECOMMENDATIONS_PER_REQUEST)));
+        let mut recommendations = Vec::new();
+        while !pager.is_done() && (recommendations.len() as u64) < count {
+            let response = self
+                .client
+                .cf_recommendation_user_recording(user, pager.page_size(), Some(pager.offset()))
+                .await?;
+            let page = response.map(|response| Page {
+                items: response.payload.mbids,
+                total: Some(response.payload.total_mbid_count),
+            });
+            recommendations.extend(pager.next_page(page).into_iter().map(|r| r.recording_mbid));
+        }
+        recommendations.truncate(usize::try_from(count).unwrap_or(usize::MAX));
+        if recommendations.is_empty() {
+            return Ok(None);
+        }
+
+        self.playlist_create(playlist.recordings(recommendations))
+            .await
+            .map(Some)
+    }
+
+    /// Add recordings to a playlist owned by (or shared with) the authenticated user.
+    /// See [`ListenBrainz::playlist_add_recordings`](crate::ListenBrainz::playlist_add_recordings).
+    pub async fn playlist_add_recordings(
+        &self,
+        playlist_mbid: &str,
+        recording_mbids: &[&str],
+        position: Option<u64>,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+        let playlist_mbid = Mbid::new(playlist_mbid)?;
+
+        for (chunk, position) in playlist_item_chunks(recording_mbids, position)? {
+            self.client
+                .playlist_item_add(token, &playlist_mbid, position, chunk)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move recordings within a playlist.
+    /// See [`ListenBrainz::playlist_move_recordings`](crate::ListenBrainz::playlist_move_recordings).
+    pub async fn playlist_move_recordings(
+        &self,
+        playlist_mbid: &str,
+        recording_mbid: &str,
+        from: u64,
+        to: u64,
+        count: u64,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let data = PlaylistItemMove {
+            mbid: Mbid::new(recording_mbid)?.into(),
+            from,
+            to,
+            count,
+        };
+        self.client
+            .playlist_item_move(token, &Mbid::new(playlist_mbid)?, data)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove recordings from a playlist.
+    /// See [`ListenBrainz::playlist_remove_recordings`](crate::ListenBrainz::playlist_remove_recordings).
+    pub async fn playlist_remove_recordings(
+        &self,
+        playlist_mbid: &str,
+        position: u64,
+        count: u64,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let data = PlaylistItemDelete {
+            index: position,
+            count,
+        };
+        self.client
+            .playlist_item_delete(token, &Mbid::new(playlist_mbid)?, data)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch the current state of a playlist, apply `edit` to it and save the result.
+    async fn edit_playlist(
+        &self,
+        playlist_mbid: &str,
+        edit: impl FnOnce(&mut PlaylistEditPlaylist),
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+        let playlist_mbid = Mbid::new(playlist_mbid)?;
+
+        let current = self
+            .client
+            .get_playlist(Some(token), &playlist_mbid)
+            .await?;
+        let mut playlist = PlaylistEditPlaylist::from(&current.playlist);
+        edit(&mut playlist);
+
+        self.client
+            .playlist_edit(token, &playlist_mbid, PlaylistEdit { playlist })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Change the title of a playlist.
+    /// See [`ListenBrainz::playlist_set_title`](crate::ListenBrainz::playlist_set_title).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_set_title(&self, playlist_mbid: &str, title: &str) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.title = Some(title.to_string());
+        })
+        .await
+    }
+
+    /// Change the annotation (description) of a playlist.
+    /// See [`ListenBrainz::playlist_set_annotation`](crate::ListenBrainz::playlist_set_annotation).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_set_annotation(
+        &self,
+        playlist_mbid: &str,
+        annotation: &str,
+    ) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.annotation = Some(annotation.to_string());
+        })
+        .await
+    }
+
+    /// Make a playlist public or private.
+    /// See [`ListenBrainz::playlist_set_public`](crate::ListenBrainz::playlist_set_public).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_set_public(
+        &self,
+        playlist_mbid: &str,
+        public: bool,
+    ) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.extension.musicbrainz.public = Some(public);
+        })
+        .await
+    }
+
+    /// Add a collaborator to a playlist. Does nothing if the user already is a collaborator.
+    /// See [`ListenBrainz::playlist_add_collaborator`](crate::ListenBrainz::playlist_add_collaborator).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_add_collaborator(
+        &self,
+        playlist_mbid: &str,
+        user_name: &str,
+    ) -> Result<(), Error> {
+        let user_name = UserName::new(user_name)?;
+
+        self.edit_playlist(playlist_mbid, |playlist| {
+            let collaborators = playlist
+                .extension
+                .musicbrainz
+                .collaborators
+                .get_or_insert_with(Vec::new);
+            if !collaborators.iter().any(|c| c == user_name.as_str()) {
+                collaborators.push(user_name.into());
+            }
+        })
+        .await
+    }
+
+    /// Remove a collaborator from a playlist.
+    /// See [`ListenBrainz::playlist_remove_collaborator`](crate::ListenBrainz::playlist_remove_collaborator).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_remove_collaborator(
+        &self,
+        playlist_mbid: &str,
+        user_name: &str,
+    ) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            if let Some(collaborators) = &mut playlist.extension.musicbrainz.collaborators {
+                collaborators.retain(|c| c != user_name);
+            }
+        })
+        .await
+    }
+
+    /// Export a playlist to a linked streaming service, and return the URL of the new playlist.
+    /// See [`ListenBrainz::playlist_export`](crate::ListenBrainz::playlist_export).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_export(
+        &self,
+        playlist_mbid: &str,
+        service: MusicService,
+    ) -> Result<String, Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let response = self
+            .client
+            .playlist_export(token, &Mbid::new(playlist_mbid)?, service)
+            .await?;
+
+        Ok(response.external_url)
+    }
+
+    /// Import a playlist from a linked streaming service, and return the MBID of the ListenBrainz playlist.
+    /// See [`ListenBrainz::playlist_import`](crate::ListenBrainz::playlist_import).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn playlist_import(
+        &self,
+        service: MusicService,
+        playlist_id: &str,
+    ) -> Result<String, Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let imported = self
+            .client
+            .playlist_service_tracks(token, service, playlist_id)
+            .await?
+            .playlist;
+        if let Some(mbid) = saved_playlist_mbid(&imported) {
+            return Ok(mbid);
+        }
+
+        self.create_playlist(token, playlist_create_from(&imported, playlist_id))
+            .await
+    }
+
+    /// Copy every playlist created for the authenticated user that is not in `archive` yet,
+    /// and return the new copies.
+    /// See [`ListenBrainz::archive_created_for_playlists`](crate::ListenBrainz::archive_created_for_playlists).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn archive_created_for_playlists(
+        &self,
+        archive: &mut PlaylistArchive,
+    ) -> Result<Vec<ArchivedPlaylist>, Error> {
+        let (token, user) = self
+            .auth
+            .as_ref()
+            .map(|auth| (auth.token.as_str(), &auth.user))
+            .ok_or(Error::NotAuthenticated)?;
+
+        let mut pager = OffsetPager::new(None);
+        let mut playlists = Vec::new();
+        while !pager.is_done() {
+            let response = self
+                .client
+                .user_playlists_created_for(
+                    Some(token),
+                    user,
+                    pager.page_size(),
+                    Some(pager.offset()),
+                )
+                .await?;
+            let page = Page {
+                items: response.playlists,
+                total: Some(response.playlist_count),
+            };
+            playlists.extend(pager.next_page(Some(page)).into_iter().map(|p| p.playlist));
+        }
+
+        let mut archived = Vec::new();
+        for playlist in unarchived_playlists(playlists, archive) {
+            let source_mbid = playlist.mbid().unwrap_or_default().to_string();
+            let response = self
+                .client
+                .playlist_copy(token, &Mbid::new(&source_mbid)?)
+                .await?;
+            archive.insert(&source_mbid, &response.playlist_mbid);
+            archived.push(ArchivedPlaylist {
+                source_mbid,
+                copy_mbid: response.playlist_mbid,
+                title: playlist.title,
+            });
+        }
+
+        Ok(archived)
+    }
+
+    /// Get the listens of a user that are not mapped to a MusicBrainz recording, newest first.
+    /// See [`ListenBrainz::unmapped_listens`](crate::ListenBrainz::unmapped_listens).
+    pub async fn unmapped_listens(
+        &self,
+        user_name: &str,
+        min_ts: Option<i64>,
+        max_ts: Option<i64>,
+    ) -> Result<Vec<UserListensListen>, Error> {
+        let user_name = UserName::new(user_name)?;
+        let mut pager = ListensPager::new(min_ts, max_ts);
+        let mut unmapped = Vec::new();
+
+        while !pager.is_done() {
+            let listens = self
+                .client
+                .user_listens(
+                    &user_name,
+                    None,
+                    pager.max_ts(),
+                    Some(MAX_LISTENS_PER_REQUEST),
+                )
+                .await?
+                .payload
+                .listens;
+
+            unmapped.extend(
+                pager
+                    .next_page(listens)
+                    .into_iter()
+                    .filter(|listen| listen.track_metadata.mbid_mapping.is_none()),
+            );
+        }
+
+        Ok(unmapped)
+    }
+
+    /// Rate a recording that was recommended to the authenticated user.
+    /// See [`ListenBrainz::submit_recommendation_feedback`](crate::ListenBrainz::submit_recommendation_feedback).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn submit_recommendation_feedback(
+        &self,
+        recording_mbid: &str,
+        rating: RecommendationFeedbackRating,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client
+            .recommendation_feedback_submit(
+                token,
+                RecommendationFeedbackSubmit {
+                    recording_mbid,
+                    rating,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Delete the rating of a recording that was recommended to the authenticated user.
+    /// See [`ListenBrainz::delete_recommendation_feedback`](crate::ListenBrainz::delete_recommendation_feedback).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn delete_recommendation_feedback(&self, recording_mbid: &str) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client
+            .recommendation_feedback_delete(token, RecommendationFeedbackDelete { recording_mbid })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Map the listens of a recording (by MSID) to a MusicBrainz recording.
+    /// See [`ListenBrainz::submit_manual_mapping`](crate::ListenBrainz::submit_manual_mapping).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub async fn submit_manual_mapping(
+        &self,
+        recording_msid: &str,
+        recording_mbid: &str,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client
+            .metadata_submit_manual_mapping(
+                token,
+                MetadataSubmitManualMapping {
+                    recording_msid,
+                    recording_mbid,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl Default for AsyncListenBrainz {
+    fn default() -> Self {
+        Self::new()
+    }
+}
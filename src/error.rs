@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use attohttpc::Response;
+use crate::raw::transport::HttpResponse;
 
 /// Represents errors that can occur while interacting with the API.
 #[derive(Debug, thiserror::Error)]
@@ -13,15 +13,19 @@ pub enum Error {
 
         /// A message describing the error.
         error: String,
+
+        /// The number of times the request was attempted, including retries.
+        /// See [`RetryPolicy`](crate::raw::RetryPolicy).
+        attempts: u32,
     },
 
     /// The request or response data could not be converted into or from JSON.
     #[error("could not convert request or response data into or from JSON")]
-    Json(#[source] attohttpc::Error),
+    Json(#[source] serde_json::Error),
 
     /// There was some other HTTP error while interacting with the API.
     #[error("HTTP error")]
-    Http(#[source] attohttpc::Error),
+    Http(#[source] Box<dyn std::error::Error + Send + Sync>),
 
     /// The token that was attempted to be used for authentication is invalid.
     #[error("invalid authentication token")]
@@ -30,20 +34,59 @@ pub enum Error {
     /// Tried to access a service that requires authentication.
     #[error("not authenticated")]
     NotAuthenticated,
+
+    /// The user name is not valid. See [`UserName`](crate::raw::UserName).
+    #[error("invalid user name: {0:?}")]
+    InvalidUserName(String),
+
+    /// The MBID is not a valid UUID. See [`Mbid`](crate::raw::Mbid).
+    #[error("invalid MBID: {0:?}")]
+    InvalidMbid(String),
+
+    /// The playlist is not valid, e.g. because its title is empty.
+    /// See [`PlaylistBuilder`](crate::PlaylistBuilder).
+    #[error("invalid playlist: {0}")]
+    InvalidPlaylist(String),
+
+    /// A local file could not be read or written.
+    #[error("I/O error")]
+    Io(#[source] std::io::Error),
+
+    /// A playlist could not be converted into or from XSPF.
+    #[error("could not convert playlist into or from XSPF: {0}")]
+    Xspf(String),
 }
 
 impl Error {
     /// If the response is a client or server error (status [400-599]),
     /// deserialize it into `Error::Api`. Otherwise, return the original response.
-    pub(crate) fn try_from_error_response(response: Response) -> Result<Response, Self> {
-        let status = response.status();
-        if status.is_client_error() || status.is_server_error() {
-            let api_error: ApiError = response.json()?;
+    ///
+    /// Error responses that don't come from the API itself (e.g. a `502 Bad Gateway`
+    /// page from a proxy) use the status code and the response body as the error.
+    pub(crate) fn try_from_error_response(response: HttpResponse) -> Result<HttpResponse, Self> {
+        if (400..600).contains(&response.status) {
+            let api_error = response.json().unwrap_or_else(|_| ApiError {
+                code: response.status,
+                error: String::from_utf8_lossy(&response.body).trim().to_string(),
+            });
             Err(api_error.into())
         } else {
             Ok(response)
         }
     }
+
+    /// Set the number of attempts of an [`Error::Api`] to `attempts`.
+    /// Other errors are returned as is.
+    pub(crate) fn with_attempts(self, attempts: u32) -> Self {
+        match self {
+            Self::Api { code, error, .. } => Self::Api {
+                code,
+                error,
+                attempts,
+            },
+            error => error,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,15 +100,31 @@ impl From<ApiError> for Error {
         Error::Api {
             code: api_error.code,
             error: api_error.error,
+            attempts: 1,
         }
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
 impl From<attohttpc::Error> for Error {
     fn from(error: attohttpc::Error) -> Self {
-        match error.kind() {
-            attohttpc::ErrorKind::Json(_) => Self::Json(error),
-            _ => Self::Http(error),
+        match error.into_kind() {
+            attohttpc::ErrorKind::Json(error) => Self::Json(error),
+            // Expose I/O errors directly, so their kind can be inspected through `source()`
+            attohttpc::ErrorKind::Io(error) => Self::Http(Box::new(error)),
+            kind => Self::Http(Box::new(attohttpc::Error::from(kind))),
         }
     }
 }
+
+#[cfg(any(feature = "async", feature = "keep-alive"))]
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(Box::new(error))
+    }
+}
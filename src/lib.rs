@@ -47,8 +47,17 @@
 //! - `tls-rustls-native-roots` to use `rustls` with root certificates loaded from the rustls-native-certs crate
 //! - `tls-native` to use `native-tls` (requires Rust >= 1.80)
 //! - `tls-native-vendored` to use `native-tls` and activate the `vendored` feature
+//!
 //! These are analogous to [attohttpc](https://docs.rs/attohttpc/latest/attohttpc/#features), the
 //! underlying HTTP client.
+//!
+//! The `async` feature enables the asynchronous `raw::AsyncClient` and `AsyncListenBrainz`
+//! clients, which are based on [reqwest](https://docs.rs/reqwest) and need to run inside a
+//! Tokio runtime. The TLS features above apply to them as well.
+//!
+//! The `keep-alive` feature enables [`ClientBuilder::keep_alive`](raw::ClientBuilder), which
+//! makes [`raw::Client`] and [`ListenBrainz`] reuse connections between requests, using the
+//! blocking client of reqwest.
 
 #![deny(
     missing_docs,
@@ -57,9 +66,17 @@
     unstable_features
 )]
 
+mod archive;
+#[cfg(feature = "async")]
+mod async_wrapper;
 mod error;
+#[cfg(feature = "local")]
+pub mod local;
 pub mod raw;
 mod wrapper;
 
+pub use crate::archive::{ArchivedPlaylist, PlaylistArchive};
+#[cfg(feature = "async")]
+pub use crate::async_wrapper::AsyncListenBrainz;
 pub use crate::error::Error;
-pub use crate::wrapper::ListenBrainz;
+pub use crate::wrapper::{ListenBrainz, ListenBrainzBuilder, PlaylistBuilder};
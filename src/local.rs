@@ -0,0 +1,40 @@
+//! Matching ListenBrainz playlists with a local music library.
+//!
+//! Requires the `local` feature.
+//!
+//! A [`Library`] is an index of local audio files, built by scanning a directory and reading
+//! the MusicBrainz recording ID, artist and title from the tags of every file
+//! (ID3v2, Vorbis comments, MP4 atoms, APE tags, ...).
+//! [`export_m3u8`] looks up the tracks of a playlist in a library, e.g. to play the
+//! playlists created for a user (Weekly Jams, Weekly Exploration) on an offline player,
+//! and writes an M3U8 playlist with the files that were found.
+//!
+//! The other way around, [`playlist_from_dir`] and [`playlist_from_m3u`] build a playlist
+//! from the recording IDs in the tags of local files, to be created on ListenBrainz.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use listenbrainz::local::{self, Library};
+//! # use listenbrainz::raw::{Client, Mbid};
+//! let client = Client::new();
+//! let playlist_mbid = Mbid::new("f316bb0e-8e26-44f8-a802-6d2a3688fc7d")?;
+//! let playlist = client.get_playlist(None, &playlist_mbid)?.playlist;
+//!
+//! let library = Library::scan("/home/koen/Music")?;
+//! let unmatched = local::export_m3u8(&playlist, &library, "/home/koen/Music/jams.m3u8")?;
+//! for track in unmatched {
+//!     println!("not found: {:?} by {:?}", track.title, track.creator);
+//! }
+//! # Ok::<(), listenbrainz::Error>(())
+//! ```
+
+mod library;
+mod m3u;
+mod playlist;
+
+pub use self::library::{Library, LocalTrack};
+pub use self::m3u::{export_m3u8, match_playlist, M3uEntry, M3uPlaylist, PlaylistMatch};
+pub use self::playlist::{
+    playlist_from_dir, playlist_from_files, playlist_from_m3u, LocalPlaylist,
+};
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use lofty::prelude::*;
+
+use crate::raw::jspf;
+use crate::raw::Mbid;
+use crate::Error;
+
+/// File extensions of the audio formats whose tags can be read.
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "aac", "aif", "aifc", "aiff", "ape", "flac", "m4a", "m4b", "mp3", "mp4", "mpc", "oga", "ogg",
+    "opus", "spx", "wav", "wv",
+];
+
+/// A local audio file and the tags that are used to match it with tracks of a playlist.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LocalTrack {
+    /// Path of the file.
+    pub path: PathBuf,
+
+    /// MBID of the recording (the `MUSICBRAINZ_TRACKID` tag).
+    pub recording_mbid: Option<String>,
+
+    /// Artist of the track.
+    pub artist: Option<String>,
+
+    /// Title of the track.
+    pub title: Option<String>,
+
+    /// Album of the track.
+    pub album: Option<String>,
+
+    /// Duration of the audio.
+    pub duration: Option<Duration>,
+}
+
+impl LocalTrack {
+    /// Read the tags of an audio file.
+    ///
+    /// A recording MBID that is not a valid MBID is ignored.
+    /// Returns [`Error::Io`] if the file cannot be read or is not a supported audio file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = lofty::read_from_path(path)
+            .map_err(|error| Error::Io(io::Error::new(io::ErrorKind::InvalidData, error)))?;
+
+        let duration = Some(file.properties().duration()).filter(|duration| !duration.is_zero());
+        let mut track = Self {
+            path: path.to_path_buf(),
+            duration,
+            ..Self::default()
+        };
+
+        // Files can have multiple tags, e.g. RIFF INFO and ID3v2 in WAV files;
+        // take every field from the primary tag if it has it, or else from the first that does.
+        let primary = file.primary_tag_type();
+        let mut tags: Vec<_> = file.tags().iter().collect();
+        tags.sort_by_key(|tag| tag.tag_type() != primary);
+
+        track.recording_mbid = tags.iter().find_map(|tag| {
+            tag.get_string(&ItemKey::MusicBrainzRecordingId)
+                .and_then(|mbid| Mbid::new(mbid.trim()).ok())
+                .map(String::from)
+        });
+        track.artist = tags
+            .iter()
+            .find_map(|tag| tag.artist())
+            .map(|artist| artist.into_owned());
+        track.title = tags
+            .iter()
+            .find_map(|tag| tag.title())
+            .map(|title| title.into_owned());
+        track.album = tags
+            .iter()
+            .find_map(|tag| tag.album())
+            .map(|album| album.into_owned());
+
+        Ok(track)
+    }
+}
+
+/// An index of local audio files, to look up the tracks of a playlist.
+///
+/// Tracks are looked up by recording MBID, and otherwise by artist and title,
+/// ignoring differences in case and whitespace.
+/// If multiple files match, the one that was added first is used.
+#[derive(Debug, Clone, Default)]
+pub struct Library {
+    tracks: Vec<LocalTrack>,
+    by_mbid: HashMap<String, usize>,
+    by_name: HashMap<(String, String), usize>,
+    skipped: Vec<PathBuf>,
+}
+
+impl Library {
+    /// Construct an empty library.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a library from all audio files in a directory and its subdirectories,
+    /// in order of their paths.
+    ///
+    /// Audio files whose tags cannot be read are skipped, and listed in [`Library::skipped`].
+    /// Returns [`Error::Io`] if a directory cannot be read.
+    pub fn scan(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+
+        let mut paths = Vec::new();
+        collect_audio_files(&dir, &mut paths).map_err(Error::Io)?;
+        paths.sort();
+
+        let mut library = Self::new();
+        for path in paths {
+            match LocalTrack::read(&path) {
+                Ok(track) => library.add(track),
+                Err(_) => library.skipped.push(path),
+            }
+        }
+
+        Ok(library)
+    }
+
+    /// Add a track to the library.
+    pub fn add(&mut self, track: LocalTrack) {
+        let index = self.tracks.len();
+
+        if let Some(mbid) = &track.recording_mbid {
+            self.by_mbid.entry(mbid.to_lowercase()).or_insert(index);
+        }
+        if let (Some(artist), Some(title)) = (&track.artist, &track.title) {
+            self.by_name
+                .entry((normalize(artist), normalize(title)))
+                .or_insert(index);
+        }
+
+        self.tracks.push(track);
+    }
+
+    /// Get all tracks in the library.
+    pub fn tracks(&self) -> &[LocalTrack] {
+        &self.tracks
+    }
+
+    /// Get the audio files that were skipped by [`Library::scan`] because their tags could not be read.
+    pub fn skipped(&self) -> &[PathBuf] {
+        &self.skipped
+    }
+
+    /// Find a file of the recording with the given MBID.
+    pub fn find_recording(&self, recording_mbid: &str) -> Option<&LocalTrack> {
+        self.by_mbid
+            .get(&recording_mbid.to_lowercase())
+            .map(|&index| &self.tracks[index])
+    }
+
+    /// Find a file by artist and title.
+    pub fn find_by_name(&self, artist: &str, title: &str) -> Option<&LocalTrack> {
+        self.by_name
+            .get(&(normalize(artist), normalize(title)))
+            .map(|&index| &self.tracks[index])
+    }
+
+    /// Find a file for a track of a playlist, by the recording MBID in its identifiers,
+    /// or otherwise by its creator and title.
+    pub fn find(&self, track: &jspf::Track) -> Option<&LocalTrack> {
+        track
+            .recording_mbid()
+            .and_then(|mbid| self.find_recording(mbid))
+            .or_else(|| match (&track.creator, &track.title) {
+                (Some(artist), Some(title)) => self.find_by_name(artist, title),
+                _ => None,
+            })
+    }
+}
+
+impl Extend<LocalTrack> for Library {
+    fn extend<I: IntoIterator<Item = LocalTrack>>(&mut self, tracks: I) {
+        for track in tracks {
+            self.add(track);
+        }
+    }
+}
+
+impl FromIterator<LocalTrack> for Library {
+    fn from_iter<I: IntoIterator<Item = LocalTrack>>(tracks: I) -> Self {
+        let mut library = Self::new();
+        library.extend(tracks);
+        library
+    }
+}
+
+/// Check if a file has the extension of a supported audio format.
+pub(crate) fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Recursively collect the audio files in `dir`, without following symbolic links to directories.
+pub(crate) fn collect_audio_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_audio_files(&path, paths)?;
+        } else if path.is_file() && is_audio_file(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalize an artist or title for comparison.
+fn normalize(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['\u{2018}', '\u{2019}'], "'")
+        .replace(['\u{201c}', '\u{201d}'], "\"")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBID: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+
+    fn local(path: &str, mbid: Option<&str>, artist: &str, title: &str) -> LocalTrack {
+        LocalTrack {
+            path: PathBuf::from(path),
+            recording_mbid: mbid.map(String::from),
+            artist: Some(artist.to_string()),
+            title: Some(title.to_string()),
+            ..LocalTrack::default()
+        }
+    }
+
+    fn track(mbid: Option<&str>, creator: Option<&str>, title: Option<&str>) -> jspf::Track {
+        jspf::Track {
+            identifier: mbid
+                .map(|mbid| format!("https://musicbrainz.org/recording/{mbid}"))
+                .into_iter()
+                .collect(),
+            creator: creator.map(String::from),
+            title: title.map(String::from),
+            ..jspf::Track::default()
+        }
+    }
+
+    fn path(track: Option<&LocalTrack>) -> Option<&str> {
+        track.map(|track| track.path.to_str().unwrap())
+    }
+
+    #[test]
+    fn finds_tracks_by_mbid_before_name() {
+        let library: Library = [
+            local("by-name.flac", None, "Radiohead", "Airbag"),
+            local("by-mbid.flac", Some(MBID), "Other", "Other"),
+        ]
+        .into_iter()
+        .collect();
+
+        let airbag = track(Some(MBID), Some("Radiohead"), Some("Airbag"));
+        assert_eq!(path(library.find(&airbag)), Some("by-mbid.flac"));
+        assert_eq!(
+            path(library.find_recording(&MBID.to_uppercase())),
+            Some("by-mbid.flac")
+        );
+
+        // An unknown MBID falls back to the name
+        let unknown = "00000000-0000-0000-0000-000000000000";
+        let airbag = track(Some(unknown), Some("Radiohead"), Some("Airbag"));
+        assert_eq!(path(library.find(&airbag)), Some("by-name.flac"));
+    }
+
+    #[test]
+    fn finds_tracks_by_normalized_artist_and_title() {
+        let library: Library = [local(
+            "dont.flac",
+            None,
+            "The  Artist",
+            "Don\u{2019}t Say \u{201c}Never\u{201d}",
+        )]
+        .into_iter()
+        .collect();
+
+        let dont = track(None, Some(" the artist "), Some("DON'T say \"never\""));
+        assert_eq!(path(library.find(&dont)), Some("dont.flac"));
+        assert_eq!(
+            path(library.find_by_name("THE ARTIST", "don't  say \"never\"")),
+            Some("dont.flac")
+        );
+
+        assert!(library
+            .find(&track(None, None, Some("Don't Say")))
+            .is_none());
+        assert!(library
+            .find(&track(None, Some("The Artist"), Some("Other")))
+            .is_none());
+    }
+
+    #[test]
+    fn uses_the_first_added_file_if_several_match() {
+        let mut library: Library = [
+            local("first.flac", Some(MBID), "Radiohead", "Airbag"),
+            local("second.flac", Some(MBID), "Radiohead", "Airbag"),
+        ]
+        .into_iter()
+        .collect();
+        library.add(local("third.flac", None, "radiohead", "airbag"));
+
+        assert_eq!(library.tracks().len(), 3);
+        assert_eq!(path(library.find_recording(MBID)), Some("first.flac"));
+        assert_eq!(
+            path(library.find_by_name("Radiohead", "Airbag")),
+            Some("first.flac")
+        );
+    }
+
+    #[test]
+    fn collects_audio_files_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        for file in [
+            "x.FLAC",
+            "notes.txt",
+            "a/y.mp3",
+            "a/b/z.opus",
+            "a/b/cover.jpg",
+        ] {
+            fs::write(root.join(file), b"not audio").unwrap();
+        }
+
+        let mut paths = Vec::new();
+        collect_audio_files(root, &mut paths).unwrap();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                root.join("a/b/z.opus"),
+                root.join("a/y.mp3"),
+                root.join("x.FLAC")
+            ]
+        );
+
+        // None of the files can actually be read as audio
+        let library = Library::scan(root).unwrap();
+        assert!(library.tracks().is_empty());
+        assert_eq!(library.skipped().len(), 3);
+
+        assert!(matches!(
+            Library::scan(root.join("missing")),
+            Err(Error::Io(_))
+        ));
+    }
+}
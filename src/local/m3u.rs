@@ -0,0 +1,463 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use percent_encoding::percent_decode_str;
+
+use super::library::Library;
+use crate::raw::jspf;
+use crate::Error;
+
+/// An extended M3U playlist.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct M3uPlaylist {
+    /// Title of the playlist (the `#PLAYLIST` directive).
+    pub title: Option<String>,
+
+    /// The entries of the playlist, in order.
+    pub entries: Vec<M3uEntry>,
+}
+
+/// An entry of an [`M3uPlaylist`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct M3uEntry {
+    /// Path of the file.
+    pub path: PathBuf,
+
+    /// Duration of the track, written in whole seconds (the `#EXTINF` directive).
+    pub duration: Option<Duration>,
+
+    /// Title to display for the track, usually `Artist - Title` (the `#EXTINF` directive).
+    pub title: Option<String>,
+}
+
+impl M3uPlaylist {
+    /// Write the playlist in the extended M3U format.
+    pub fn to_m3u8(&self) -> String {
+        let mut m3u = String::from("#EXTM3U\n");
+        if let Some(title) = &self.title {
+            writeln!(m3u, "#PLAYLIST:{}", single_line(title)).unwrap();
+        }
+
+        for entry in &self.entries {
+            if entry.duration.is_some() || entry.title.is_some() {
+                let duration = entry
+                    .duration
+                    .map_or(-1, |duration| duration.as_secs() as i64);
+                let title = entry.title.as_deref().map(single_line).unwrap_or_default();
+                writeln!(m3u, "#EXTINF:{duration},{title}").unwrap();
+            }
+            writeln!(m3u, "{}", entry.path.display()).unwrap();
+        }
+
+        m3u
+    }
+
+    /// Parse a playlist in the (extended) M3U format.
+    ///
+    /// Paths are kept as they are written, except that `file://` URLs are turned into paths.
+    /// Unknown directives and comments are ignored.
+    pub fn parse(m3u: &str) -> Self {
+        let mut playlist = Self::default();
+        let mut duration = None;
+        let mut title = None;
+
+        for line in m3u.trim_start_matches('\u{feff}').lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(info) = line.strip_prefix("#EXTINF:") {
+                // `#EXTINF:<duration> [attributes],<title>`, where attributes may contain commas
+                let (info, display) = split_extinf(info);
+                duration = info
+                    .split_whitespace()
+                    .next()
+                    .and_then(|duration| duration.parse::<f64>().ok())
+                    .filter(|duration| duration.is_finite() && *duration >= 0.0)
+                    .map(Duration::from_secs_f64);
+                title = Some(display.trim().to_string()).filter(|title| !title.is_empty());
+            } else if let Some(name) = line.strip_prefix("#PLAYLIST:") {
+                playlist.title = Some(name.trim().to_string());
+            } else if !line.starts_with('#') {
+                playlist.entries.push(M3uEntry {
+                    path: entry_path(line),
+                    duration: duration.take(),
+                    title: title.take(),
+                });
+            }
+        }
+
+        playlist
+    }
+
+    /// Read a playlist from an M3U or M3U8 file.
+    ///
+    /// Relative paths are resolved against the directory of the file.
+    /// Files that are not valid UTF-8 are read as Latin-1, as is common for `.m3u` files.
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(Error::Io)?;
+        let m3u = String::from_utf8(bytes)
+            .unwrap_or_else(|error| error.into_bytes().iter().map(|&b| b as char).collect());
+
+        let mut playlist = Self::parse(&m3u);
+        if let Some(dir) = path.parent() {
+            for entry in &mut playlist.entries {
+                if entry.path.is_relative() {
+                    entry.path = dir.join(&entry.path);
+                }
+            }
+        }
+
+        Ok(playlist)
+    }
+
+    /// Write the playlist to a UTF-8 encoded (M3U8) file.
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        fs::write(path, self.to_m3u8()).map_err(Error::Io)
+    }
+}
+
+/// The result of [`match_playlist`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlaylistMatch {
+    /// A playlist with the files of the tracks that were found.
+    pub m3u: M3uPlaylist,
+
+    /// The tracks that were not found in the library.
+    pub unmatched: Vec<jspf::Track>,
+}
+
+/// Look up the tracks of a playlist in a library, and build an M3U playlist
+/// with the files that were found. See [`Library::find`].
+pub fn match_playlist(playlist: &jspf::PlaylistInfo, library: &Library) -> PlaylistMatch {
+    let mut result = PlaylistMatch {
+        m3u: M3uPlaylist {
+            title: playlist.title.clone(),
+            entries: Vec::new(),
+        },
+        unmatched: Vec::new(),
+    };
+
+    for track in &playlist.track {
+        let Some(local) = library.find(track) else {
+            result.unmatched.push(track.clone());
+            continue;
+        };
+
+        let artist = track.creator.as_ref().or(local.artist.as_ref());
+        let title = track.title.as_ref().or(local.title.as_ref());
+        let title = match (artist, title) {
+            (Some(artist), Some(title)) => Some(format!("{artist} - {title}")),
+            (None, Some(title)) => Some(title.clone()),
+            _ => None,
+        };
+
+        result.m3u.entries.push(M3uEntry {
+            path: local.path.clone(),
+            duration: local.duration.or(track.duration.map(Duration::from_millis)),
+            title,
+        });
+    }
+
+    result
+}
+
+/// Look up the tracks of a playlist in a library, write an M3U8 file with the files
+/// that were found, and return the tracks that were not found.
+///
+/// Files inside the directory of the M3U8 file are written as relative paths,
+/// so that the directory can be copied to another device as a whole.
+///
+/// Returns [`Error::Io`] if the file cannot be written.
+pub fn export_m3u8(
+    playlist: &jspf::PlaylistInfo,
+    library: &Library,
+    path: impl AsRef<Path>,
+) -> Result<Vec<jspf::Track>, Error> {
+    let path = path.as_ref();
+    let mut result = match_playlist(playlist, library);
+
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    for entry in &mut result.m3u.entries {
+        if let Ok(relative) = entry.path.strip_prefix(&dir) {
+            entry.path = relative.to_path_buf();
+        }
+    }
+
+    result.m3u.write(path)?;
+
+    Ok(result.unmatched)
+}
+
+/// Get the path of an entry, which may also be a `file://` URL.
+fn entry_path(entry: &str) -> PathBuf {
+    match entry.strip_prefix("file://") {
+        Some(path) => {
+            // Skip the host, which is usually empty or `localhost`
+            let path = &path[path.find('/').unwrap_or(0)..];
+            PathBuf::from(percent_decode_str(path).decode_utf8_lossy().into_owned())
+        }
+        None => PathBuf::from(entry),
+    }
+}
+
+/// Split the value of an `#EXTINF` directive at the first comma outside of quoted attributes.
+fn split_extinf(info: &str) -> (&str, &str) {
+    let mut quoted = false;
+    for (index, c) in info.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => return (&info[..index], &info[index + 1..]),
+            _ => {}
+        }
+    }
+    (info, "")
+}
+
+/// Replace line breaks, which would end a directive.
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::LocalTrack;
+
+    const MBID: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+
+    fn entry(path: &str, duration: Option<u64>, title: Option<&str>) -> M3uEntry {
+        M3uEntry {
+            path: PathBuf::from(path),
+            duration: duration.map(Duration::from_secs),
+            title: title.map(String::from),
+        }
+    }
+
+    #[test]
+    fn parses_extinf_directives() {
+        let playlist = M3uPlaylist::parse(
+            "#EXTM3U\n\
+             #PLAYLIST: Weekly Jams \n\
+             \n\
+             #EXTINF:284,Radiohead - Airbag\n\
+             Radiohead/01 Airbag.flac\n\
+             # a comment\n\
+             #EXTINF:12.5 tvg-id=\"x\" group-title=\"a,b\",Title\n\
+             b.mp3\n\
+             #EXTINF:-1,\n\
+             c.mp3\n\
+             d.mp3\n",
+        );
+
+        assert_eq!(playlist.title.as_deref(), Some("Weekly Jams"));
+        assert_eq!(
+            playlist.entries,
+            [
+                entry(
+                    "Radiohead/01 Airbag.flac",
+                    Some(284),
+                    Some("Radiohead - Airbag")
+                ),
+                M3uEntry {
+                    duration: Some(Duration::from_millis(12500)),
+                    ..entry("b.mp3", None, Some("Title"))
+                },
+                entry("c.mp3", None, None),
+                entry("d.mp3", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_playlists_with_a_byte_order_mark_and_crlf() {
+        let playlist = M3uPlaylist::parse("\u{feff}#EXTM3U\r\n#EXTINF:1,A\r\na.mp3\r\n");
+        assert_eq!(playlist.entries, [entry("a.mp3", Some(1), Some("A"))]);
+    }
+
+    #[test]
+    fn parses_file_urls_as_paths() {
+        let playlist = M3uPlaylist::parse(
+            "file:///music/Sigur%20R%C3%B3s/a.flac\n\
+             file://localhost/music/b.mp3\n\
+             /music/c%20d.mp3\n",
+        );
+        let paths: Vec<_> = playlist.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("/music/Sigur Rós/a.flac"),
+                PathBuf::from("/music/b.mp3"),
+                PathBuf::from("/music/c%20d.mp3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_latin1_files_and_resolves_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("playlist.m3u");
+        fs::write(
+            &path,
+            b"#EXTINF:10,Bj\xf6rk - J\xf3ga\nBj\xf6rk/J\xf3ga.mp3\n/music/abs.mp3\n",
+        )
+        .unwrap();
+
+        let playlist = M3uPlaylist::read(&path).unwrap();
+        assert_eq!(
+            playlist.entries,
+            [
+                M3uEntry {
+                    path: dir.path().join("Björk/Jóga.mp3"),
+                    ..entry("", Some(10), Some("Björk - Jóga"))
+                },
+                entry("/music/abs.mp3", None, None),
+            ]
+        );
+
+        assert!(matches!(
+            M3uPlaylist::read(dir.path().join("missing.m3u")),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn writes_extinf_directives() {
+        let playlist = M3uPlaylist {
+            title: Some("Weekly\nJams".to_string()),
+            entries: vec![
+                entry("a.flac", Some(284), Some("Radiohead - Airbag")),
+                M3uEntry {
+                    duration: Some(Duration::from_millis(1999)),
+                    ..entry("b.mp3", None, None)
+                },
+                entry("c.mp3", None, Some("Unknown\r\nduration")),
+                entry("d.mp3", None, None),
+            ],
+        };
+
+        let m3u = playlist.to_m3u8();
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n\
+             #PLAYLIST:Weekly Jams\n\
+             #EXTINF:284,Radiohead - Airbag\n\
+             a.flac\n\
+             #EXTINF:1,\n\
+             b.mp3\n\
+             #EXTINF:-1,Unknown  duration\n\
+             c.mp3\n\
+             d.mp3\n"
+        );
+        assert_eq!(M3uPlaylist::parse(&m3u).entries[0], playlist.entries[0]);
+    }
+
+    fn local(path: &str, mbid: Option<&str>, artist: &str, title: &str) -> LocalTrack {
+        LocalTrack {
+            path: PathBuf::from(path),
+            recording_mbid: mbid.map(String::from),
+            artist: Some(artist.to_string()),
+            title: Some(title.to_string()),
+            album: None,
+            duration: Some(Duration::from_secs(284)),
+        }
+    }
+
+    fn track(mbid: Option<&str>, creator: &str, title: &str) -> jspf::Track {
+        jspf::Track {
+            identifier: mbid
+                .map(|mbid| format!("https://musicbrainz.org/recording/{mbid}"))
+                .into_iter()
+                .collect(),
+            creator: Some(creator.to_string()),
+            title: Some(title.to_string()),
+            duration: Some(1000),
+            ..jspf::Track::default()
+        }
+    }
+
+    #[test]
+    fn matches_playlists_and_reports_unmatched_tracks() {
+        let library: Library = [
+            local("/music/airbag.flac", Some(MBID), "Radiohead", "Airbag"),
+            local("/music/lucky.flac", None, "Radiohead", "Lucky"),
+        ]
+        .into_iter()
+        .collect();
+        let playlist = jspf::PlaylistInfo {
+            title: Some("Jams".to_string()),
+            track: vec![
+                track(Some(MBID), "Someone else", "Another title"),
+                track(None, "Radiohead", "Missing"),
+                track(None, "radiohead", "LUCKY"),
+            ],
+            ..jspf::PlaylistInfo::default()
+        };
+
+        let result = match_playlist(&playlist, &library);
+        assert_eq!(result.m3u.title.as_deref(), Some("Jams"));
+        assert_eq!(
+            result.m3u.entries,
+            [
+                entry(
+                    "/music/airbag.flac",
+                    Some(284),
+                    Some("Someone else - Another title")
+                ),
+                entry("/music/lucky.flac", Some(284), Some("radiohead - LUCKY")),
+            ]
+        );
+        assert_eq!(result.unmatched, [playlist.track[1].clone()]);
+    }
+
+    #[test]
+    fn exports_files_in_the_playlist_directory_as_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = fs::canonicalize(dir.path()).unwrap();
+        let library: Library = [
+            local(
+                dir_path.join("a/airbag.flac").to_str().unwrap(),
+                Some(MBID),
+                "Radiohead",
+                "Airbag",
+            ),
+            local("/elsewhere/lucky.flac", None, "Radiohead", "Lucky"),
+        ]
+        .into_iter()
+        .collect();
+        let playlist = jspf::PlaylistInfo {
+            track: vec![
+                track(Some(MBID), "Radiohead", "Airbag"),
+                track(None, "Radiohead", "Lucky"),
+                track(None, "Radiohead", "Missing"),
+            ],
+            ..jspf::PlaylistInfo::default()
+        };
+
+        let path = dir.path().join("jams.m3u8");
+        let unmatched = export_m3u8(&playlist, &library, &path).unwrap();
+        assert_eq!(unmatched, [playlist.track[2].clone()]);
+
+        let written = M3uPlaylist::parse(&fs::read_to_string(&path).unwrap());
+        let paths: Vec<_> = written.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("a/airbag.flac"),
+                PathBuf::from("/elsewhere/lucky.flac")
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+
+use super::library::{collect_audio_files, LocalTrack};
+use super::m3u::M3uPlaylist;
+use crate::{Error, PlaylistBuilder};
+
+/// A playlist built from local audio files, by [`playlist_from_files`], [`playlist_from_dir`]
+/// or [`playlist_from_m3u`].
+#[derive(Debug, Clone)]
+pub struct LocalPlaylist {
+    /// The playlist, with the recordings of the files that have a recording MBID.
+    ///
+    /// Pass it to [`ListenBrainz::playlist_create`](crate::ListenBrainz::playlist_create),
+    /// or use [`PlaylistBuilder::build`] to get the request for
+    /// [`Client::playlist_create`](crate::raw::Client::playlist_create).
+    pub playlist: PlaylistBuilder,
+
+    /// The files that were added to the playlist.
+    pub tracks: Vec<LocalTrack>,
+
+    /// The files that were left out because they have no recording MBID.
+    pub without_mbid: Vec<LocalTrack>,
+
+    /// The files that were left out because their tags could not be read.
+    pub unreadable: Vec<PathBuf>,
+}
+
+/// Build a playlist with the given title from audio files, in the given order,
+/// using the recording MBID (`MUSICBRAINZ_TRACKID`) in their tags.
+/// See [`LocalPlaylist`].
+pub fn playlist_from_files<I>(title: &str, paths: I) -> LocalPlaylist
+where
+    I: IntoIterator,
+    I::Item: AsRef<Path>,
+{
+    let mut result = LocalPlaylist {
+        playlist: PlaylistBuilder::new(title),
+        tracks: Vec::new(),
+        without_mbid: Vec::new(),
+        unreadable: Vec::new(),
+    };
+
+    for path in paths {
+        let path = path.as_ref();
+        match LocalTrack::read(path) {
+            Ok(track) => match &track.recording_mbid {
+                Some(mbid) => {
+                    result.playlist = result.playlist.recording(mbid);
+                    result.tracks.push(track);
+                }
+                None => result.without_mbid.push(track),
+            },
+            Err(_) => result.unreadable.push(path.to_path_buf()),
+        }
+    }
+
+    result
+}
+
+/// Build a playlist with the given title from all audio files in a directory and its
+/// subdirectories, in order of their paths. See [`playlist_from_files`].
+///
+/// Returns [`Error::Io`] if a directory cannot be read.
+pub fn playlist_from_dir(title: &str, dir: impl AsRef<Path>) -> Result<LocalPlaylist, Error> {
+    let mut paths = Vec::new();
+    collect_audio_files(dir.as_ref(), &mut paths).map_err(Error::Io)?;
+    paths.sort();
+
+    Ok(playlist_from_files(title, paths))
+}
+
+/// Build a playlist from the files of an M3U or M3U8 playlist, in order.
+/// See [`playlist_from_files`].
+///
+/// The title is taken from the `#PLAYLIST` directive, or else from the name of the file.
+///
+/// Returns [`Error::Io`] if the M3U file cannot be read.
+pub fn playlist_from_m3u(path: impl AsRef<Path>) -> Result<LocalPlaylist, Error> {
+    let path = path.as_ref();
+    let m3u = M3uPlaylist::read(path)?;
+
+    let title = m3u.title.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    Ok(playlist_from_files(
+        &title,
+        m3u.entries.iter().map(|entry| &entry.path),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+
+    use super::*;
+
+    const MBID_A: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+    const MBID_B: &str = "f316bb0e-8e26-44f8-a802-6d2a3688fc7d";
+
+    /// Write a FLAC file without audio, with the given Vorbis comments as tags.
+    fn write_flac(path: &Path, comments: &[&str]) {
+        let mut flac = Vec::from(*b"fLaC");
+
+        // STREAMINFO: 1 second of 44.1 kHz mono 16-bit audio
+        flac.extend([0, 0, 0, 34]);
+        flac.extend(4096u16.to_be_bytes());
+        flac.extend(4096u16.to_be_bytes());
+        flac.extend([0; 6]);
+        flac.extend(((44100u64 << 44) | (15 << 36) | 44100).to_be_bytes());
+        flac.extend([0; 16]);
+
+        // VORBIS_COMMENT, the last metadata block
+        let mut block = Vec::new();
+        block.extend(0u32.to_le_bytes());
+        block.extend((comments.len() as u32).to_le_bytes());
+        for comment in comments {
+            block.extend((comment.len() as u32).to_le_bytes());
+            block.extend(comment.as_bytes());
+        }
+        flac.push(0x84);
+        flac.extend(&(block.len() as u32).to_be_bytes()[1..]);
+        flac.extend(block);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(path, flac).unwrap();
+    }
+
+    fn recording(dir: &Path, name: &str, mbid: &str) {
+        write_flac(
+            &dir.join(name),
+            &[
+                "ARTIST=Radiohead",
+                &format!("TITLE={name}"),
+                &format!("MUSICBRAINZ_TRACKID={mbid}"),
+            ],
+        );
+    }
+
+    fn names(tracks: &[LocalTrack]) -> Vec<String> {
+        tracks
+            .iter()
+            .map(|track| track.title.clone().unwrap())
+            .collect()
+    }
+
+    fn identifiers(playlist: &LocalPlaylist) -> Vec<serde_json::Value> {
+        let create = playlist.playlist.clone().build().unwrap();
+        let create = serde_json::to_value(create).unwrap();
+        create["playlist"]["track"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn builds_playlists_from_directories_and_reports_left_out_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        recording(root, "b.flac", MBID_B);
+        recording(root, "a/x.flac", MBID_A);
+        write_flac(&root.join("c.flac"), &["ARTIST=Radiohead", "TITLE=c.flac"]);
+        recording(root, "d.flac", "not an MBID");
+        fs::write(root.join("e.mp3"), b"not audio").unwrap();
+        fs::write(root.join("notes.txt"), b"not audio").unwrap();
+
+        let result = playlist_from_dir("From disk", root).unwrap();
+
+        assert_eq!(names(&result.tracks), ["a/x.flac", "b.flac"]);
+        assert_eq!(result.tracks[0].recording_mbid.as_deref(), Some(MBID_A));
+        assert_eq!(result.tracks[0].path, root.join("a/x.flac"));
+        assert_eq!(names(&result.without_mbid), ["c.flac", "d.flac"]);
+        assert_eq!(result.unreadable, [root.join("e.mp3")]);
+
+        let create = serde_json::to_value(result.playlist.clone().build().unwrap()).unwrap();
+        assert_eq!(create["playlist"]["title"], "From disk");
+        assert_eq!(
+            identifiers(&result),
+            [
+                json!({ "identifier": format!("https://musicbrainz.org/recording/{MBID_A}") }),
+                json!({ "identifier": format!("https://musicbrainz.org/recording/{MBID_B}") }),
+            ]
+        );
+
+        assert!(matches!(
+            playlist_from_dir("Missing", root.join("missing")),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn builds_playlists_from_files_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        recording(root, "a.flac", MBID_A);
+        recording(root, "b.flac", MBID_B);
+
+        let result = playlist_from_files(
+            "Ordered",
+            [
+                root.join("b.flac"),
+                root.join("a.flac"),
+                root.join("b.flac"),
+            ],
+        );
+        assert_eq!(names(&result.tracks), ["b.flac", "a.flac", "b.flac"]);
+        assert_eq!(identifiers(&result).len(), 3);
+        assert_eq!(
+            identifiers(&result)[0]["identifier"],
+            format!("https://musicbrainz.org/recording/{MBID_B}")
+        );
+    }
+
+    #[test]
+    fn builds_playlists_from_m3u_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        recording(root, "music/a.flac", MBID_A);
+        write_flac(&root.join("music/b.flac"), &["TITLE=b.flac"]);
+
+        fs::write(
+            root.join("jams.m3u8"),
+            "#EXTM3U\n#PLAYLIST:Weekly Jams\nmusic/b.flac\nmusic/missing.flac\nmusic/a.flac\n",
+        )
+        .unwrap();
+        let result = playlist_from_m3u(root.join("jams.m3u8")).unwrap();
+        let create = serde_json::to_value(result.playlist.clone().build().unwrap()).unwrap();
+        assert_eq!(create["playlist"]["title"], "Weekly Jams");
+        assert_eq!(names(&result.tracks), ["music/a.flac"]);
+        assert_eq!(names(&result.without_mbid), ["b.flac"]);
+        assert_eq!(result.unreadable, [root.join("music/missing.flac")]);
+
+        fs::write(root.join("Untitled Mix.m3u"), "music/a.flac\n").unwrap();
+        let result = playlist_from_m3u(root.join("Untitled Mix.m3u")).unwrap();
+        let create = serde_json::to_value(result.playlist.build().unwrap()).unwrap();
+        assert_eq!(create["playlist"]["title"], "Untitled Mix");
+
+        assert!(matches!(
+            playlist_from_m3u(root.join("missing.m3u")),
+            Err(Error::Io(_))
+        ));
+    }
+}
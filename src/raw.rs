@@ -2,12 +2,31 @@
 //!
 //! This module is mostly a thin wrapper around the HTTP API.
 //! It contains the [`Client`] type, as well as low-level models
-//! for [request] and [response] data.
+//! for [request] and [response] data. With the `async` feature enabled,
+//! it also contains the asynchronous `AsyncClient` type.
 
+#[cfg(feature = "async")]
+mod async_client;
+mod builder;
 mod client;
 mod endpoint;
+mod ids;
 pub mod jspf;
+mod pagination;
 pub mod request;
 pub mod response;
+mod retry;
+mod throttle;
+pub mod transport;
+#[cfg(feature = "xspf")]
+pub mod xspf;
 
+#[cfg(feature = "async")]
+pub use self::async_client::AsyncClient;
+pub use self::builder::ClientBuilder;
 pub use self::client::Client;
+pub use self::ids::{Mbid, UserName};
+#[cfg(feature = "async")]
+pub(crate) use self::pagination::{ListensPager, OffsetPager, Page};
+pub use self::pagination::{OffsetIter, UserListensIter};
+pub use self::retry::RetryPolicy;
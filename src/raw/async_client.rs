@@ -0,0 +1,1132 @@
+use serde::Serialize;
+
+use super::client::API_ROOT_URL;
+use super::endpoint::Endpoint;
+use super::ids::{Mbid, UserName};
+use super::jspf;
+use super::request::*;
+use super::response::*;
+use super::transport::{AsyncTransport, HttpRequest, Method, ReqwestTransport};
+use crate::Error;
+
+/// Low-level asynchronous client that directly wraps the ListenBrainz HTTP API.
+///
+/// This is the asynchronous counterpart of [`Client`](super::Client): it exposes the
+/// same methods, which take the same arguments and return the same [request] and
+/// [response] types, but performs the requests using `reqwest` instead of blocking
+/// the calling thread.
+///
+/// Requires the `async` feature.
+///
+/// [request]: super::request
+/// [response]: super::response
+///
+/// # Errors
+///
+/// AsyncClient's methods can return the following errors:
+/// - [`Error::Api`]: the API returned a non-`2XX` status.
+/// - [`Error::Json`]: the request or response data could not be converted from or into JSON.
+/// - [`Error::Http`]: there was some other HTTP error while interacting with the API.
+///
+/// # Transport
+///
+/// Requests are performed by an [`AsyncTransport`], which is [`ReqwestTransport`] by default.
+/// Use [`AsyncClient::new_with_transport`] to perform requests in a different way, for
+/// example with a custom [`reqwest::Client`] using [`ReqwestTransport::with_client`].
+///
+/// # Limitations
+///
+/// Unlike [`Client`](super::Client), this client does not yet support:
+/// - throttling based on the rate limit ([`Client::set_throttling`](super::Client::set_throttling)),
+/// - retrying failed requests ([`Client::set_retry_policy`](super::Client::set_retry_policy)),
+///   so `attempts` is always 1,
+/// - a builder to configure timeouts, a proxy or the `User-Agent`
+///   ([`Client::builder`](super::Client::builder)),
+/// - iterators over paginated endpoints, such as
+///   [`Client::user_listens_iter`](super::Client::user_listens_iter).
+#[derive(Debug)]
+pub struct AsyncClient {
+    api_root_url: String,
+    transport: Box<dyn AsyncTransport>,
+}
+
+impl AsyncClient {
+    /// Construct a new client.
+    pub fn new() -> Self {
+        Self::new_with_url(API_ROOT_URL)
+    }
+
+    /// Construct a new client with a custom API URL.
+    pub fn new_with_url(url: impl ToString) -> Self {
+        Self::new_with_transport(url, ReqwestTransport::new())
+    }
+
+    /// Construct a new client with a custom API URL that performs its requests
+    /// using the given [`AsyncTransport`].
+    pub fn new_with_transport(
+        url: impl ToString,
+        transport: impl AsyncTransport + 'static,
+    ) -> Self {
+        Self {
+            api_root_url: url.to_string(),
+            transport: Box::new(transport),
+        }
+    }
+
+    /// Get the API URL of this client.
+    pub fn api_url(&self) -> &str {
+        &self.api_root_url
+    }
+
+    /// Helper method to build a request against an endpoint of the configured API.
+    ///
+    /// Every request of this client is built here, so that it always uses the API URL
+    /// of this client, and invalid user names or MBIDs are rejected before sending.
+    fn request(&self, method: Method, endpoint: Endpoint) -> Result<HttpRequest, Error> {
+        endpoint.validate()?;
+        Ok(HttpRequest::new(
+            method,
+            format!("{}{}", self.api_root_url, endpoint),
+        ))
+    }
+
+    /// Helper method to send a request using the transport of this client.
+    async fn send(&self, request: HttpRequest) -> Result<Attempted, Error> {
+        let response = self.transport.send(&request).await?;
+
+        Ok(Attempted {
+            response,
+            attempts: 1,
+        })
+    }
+
+    /// Helper method to perform a GET request against an endpoint
+    /// without any query parameters.
+    async fn get<R: ResponseType>(&self, endpoint: Endpoint<'_>) -> Result<R, Error> {
+        let response = self.send(self.request(Method::Get, endpoint)?).await?;
+
+        response.into_response()
+    }
+
+    /// Helper method to perform a GET request against an endpoint that is paginated with
+    /// `count` and `offset`, such as most statistics endpoints, optionally with a statistics
+    /// `range`. Returns `None` if the API has no data (`204 No Content`).
+    async fn get_paged<R: ResponseType>(
+        &self,
+        endpoint: Endpoint<'_>,
+        count: Option<u64>,
+        offset: Option<u64>,
+        range: Option<StatsRange>,
+    ) -> Result<Option<R>, Error> {
+        let mut request = self.request(Method::Get, endpoint)?;
+
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+        if let Some(range) = range {
+            request = request.param("range", range.as_str());
+        }
+
+        let response = self.send(request).await?;
+
+        response.into_optional_response()
+    }
+
+    /// Helper method to perform an unauthenticated POST request against the bulk lookup
+    /// endpoints, which expect a list of MBIDs as `{"<key>": [...]}`.
+    async fn post_mbids<R: ResponseType>(
+        &self,
+        endpoint: Endpoint<'_>,
+        key: &str,
+        mbids: &[Mbid],
+    ) -> Result<R, Error> {
+        let mbids: Vec<&str> = mbids.iter().map(Mbid::as_str).collect();
+        let request = self
+            .request(Method::Post, endpoint)?
+            .json(&serde_json::json!({ key: mbids }))?;
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Helper method to perform a POST request against an endpoint
+    /// that expects `Serialize`-able input data.
+    async fn post<D, R>(&self, endpoint: Endpoint<'_>, token: &str, data: D) -> Result<R, Error>
+    where
+        D: Serialize,
+        R: ResponseType,
+    {
+        let request = self
+            .request(Method::Post, endpoint)?
+            .token(token)
+            .json(&data)?;
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Helper method to perform a GET request against the playlist listing endpoints,
+    /// which share common query parameters and optional authentication.
+    async fn get_playlists<R: ResponseType>(
+        &self,
+        endpoint: Endpoint<'_>,
+        token: Option<&str>,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<R, Error> {
+        let mut request = self.request(Method::Get, endpoint)?;
+
+        if let Some(token) = token {
+            request = request.token(token);
+        }
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`submit-listens`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-submit-listens)
+    pub async fn submit_listens<Track: StrType, Artist: StrType, Release: StrType>(
+        &self,
+        token: &str,
+        data: SubmitListens<'_, Track, Artist, Release>,
+    ) -> Result<SubmitListensResponse, Error> {
+        self.post(Endpoint::SubmitListens, token, data).await
+    }
+
+    /// Endpoint: [`validate-token`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-validate-token)
+    pub async fn validate_token(&self, token: &str) -> Result<ValidateTokenResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::ValidateToken)?
+            .token(token);
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`delete-listen`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-delete-listen)
+    pub async fn delete_listen<T: StrType>(
+        &self,
+        token: &str,
+        data: DeleteListen<T>,
+    ) -> Result<DeleteListenResponse, Error> {
+        self.post(Endpoint::DeleteListen, token, data).await
+    }
+
+    /// Endpoint: [`user/{user_name}/playlists/collaborator`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(playlist_user_name)-playlists-collaborator)
+    pub async fn user_playlists_collaborator(
+        &self,
+        token: Option<&str>,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<UserPlaylistsCollaboratorResponse, Error> {
+        self.get_playlists(
+            Endpoint::UserPlaylistsCollaborator(user_name),
+            token,
+            count,
+            offset,
+        )
+        .await
+    }
+
+    /// Endpoint: [`user/{user_name}/playlists/createdfor`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(playlist_user_name)-playlists-createdfor)
+    pub async fn user_playlists_created_for(
+        &self,
+        token: Option<&str>,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<UserPlaylistsCollaboratorResponse, Error> {
+        self.get_playlists(
+            Endpoint::UserPlaylistsCreatedFor(user_name),
+            token,
+            count,
+            offset,
+        )
+        .await
+    }
+
+    /// Endpoint: [`user/{user_name}/similar-users`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-similar-users)
+    pub async fn user_similar_users(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserSimilarUsersResponse, Error> {
+        self.get(Endpoint::UserSimilarUsers(user_name)).await
+    }
+
+    /// Endpoint: [`user/{user_name}/listen-count`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-listen-count)
+    pub async fn user_listen_count(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserListenCountResponse, Error> {
+        self.get(Endpoint::UserListenCount(user_name)).await
+    }
+
+    /// Endpoint: [`user/{user_name}/playing-now`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-playing-now)
+    pub async fn user_playing_now(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserPlayingNowResponse, Error> {
+        self.get(Endpoint::UserPlayingNow(user_name)).await
+    }
+
+    /// Endpoint: [`user/{user_name}/similar-to/{other_user_name}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-similar-to-(other_user_name))
+    pub async fn user_similar_to(
+        &self,
+        user_name: &UserName,
+        other_user_name: &UserName,
+    ) -> Result<UserSimilarToResponse, Error> {
+        self.get(Endpoint::UserSimilarTo(user_name, other_user_name))
+            .await
+    }
+
+    /// Endpoint: [`user/{user_name}/playlists`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(playlist_user_name)-playlists)
+    pub async fn user_playlists(
+        &self,
+        token: Option<&str>,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<UserPlaylistsResponse, Error> {
+        self.get_playlists(Endpoint::UserPlaylists(user_name), token, count, offset)
+            .await
+    }
+
+    /// Endpoint: [`user/{user_name}/listens`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-listens)
+    pub async fn user_listens(
+        &self,
+        user_name: &UserName,
+        min_ts: Option<i64>,
+        max_ts: Option<i64>,
+        count: Option<u64>,
+    ) -> Result<UserListensResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::UserListens(user_name))?;
+
+        if let Some(min_ts) = min_ts {
+            request = request.param("min_ts", min_ts);
+        }
+        if let Some(max_ts) = max_ts {
+            request = request.param("max_ts", max_ts);
+        }
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`latest-import`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-latest-import) (`GET`)
+    pub async fn get_latest_import(
+        &self,
+        user_name: &UserName,
+    ) -> Result<GetLatestImportResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::LatestImport)?
+            .param("user_name", user_name);
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint:
+    /// [`playlist`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid))
+    /// (`GET`)
+    ///
+    /// A token is required to fetch private playlists.
+    pub async fn get_playlist(
+        &self,
+        token: Option<&str>,
+        playlist: &Mbid,
+    ) -> Result<GetPlaylistResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::Playlist(playlist))?;
+
+        if let Some(token) = token {
+            request = request.token(token);
+        }
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/xspf`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid)-xspf)
+    ///
+    /// A token is required to fetch private playlists.
+    pub async fn get_playlist_xspf(
+        &self,
+        token: Option<&str>,
+        playlist: &Mbid,
+    ) -> Result<GetPlaylistXspfResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::PlaylistXspf(playlist))?;
+
+        if let Some(token) = token {
+            request = request.token(token);
+        }
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`latest-import`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-latest-import) (`POST`)
+    pub async fn update_latest_import(
+        &self,
+        token: &str,
+        data: UpdateLatestImport,
+    ) -> Result<UpdateLatestImportResponse, Error> {
+        self.post(Endpoint::LatestImport, token, data).await
+    }
+
+    /// Endpoint: [`playlist/create`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-create)
+    pub async fn playlist_create(
+        &self,
+        token: &str,
+        playlist: PlaylistCreate,
+    ) -> Result<PlaylistCreateResponse, Error> {
+        self.post(Endpoint::PlaylistCreate, token, playlist).await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/delete`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-delete)
+    pub async fn playlist_delete(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+    ) -> Result<PlaylistDeleteResponse, Error> {
+        self.post(Endpoint::PlaylistDelete(playlist_mbid), token, ())
+            .await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/copy`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-copy)
+    pub async fn playlist_copy(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+    ) -> Result<PlaylistCopyResponse, Error> {
+        self.post(Endpoint::PlaylistCopy(playlist_mbid), token, ())
+            .await
+    }
+
+    /// Endpoint: [`playlist/search`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-playlist-search)
+    ///
+    /// Searches public playlists by name and description.
+    pub async fn playlist_search(
+        &self,
+        query: &str,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<PlaylistSearchResponse, Error> {
+        let mut request = self
+            .request(Method::Get, Endpoint::PlaylistSearch)?
+            .param("query", query);
+
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+
+        let response = self.send(request).await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`playlist/edit/{playlist_mbid}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-edit-(playlist_mbid))
+    pub async fn playlist_edit(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistEdit,
+    ) -> Result<PlaylistEditResponse, Error> {
+        self.post(Endpoint::PlaylistEdit(playlist_mbid), token, data)
+            .await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/add`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-add)
+    /// or [`playlist/{playlist_mbid}/item/add/{offset}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-add-(int-offset))
+    ///
+    /// Without an `offset`, the tracks are appended to the end of the playlist.
+    pub async fn playlist_item_add(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        offset: Option<u64>,
+        data: PlaylistItemAdd,
+    ) -> Result<PlaylistItemAddResponse, Error> {
+        self.post(
+            Endpoint::PlaylistItemAdd(playlist_mbid, offset),
+            token,
+            data,
+        )
+        .await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/move`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-move)
+    pub async fn playlist_item_move(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistItemMove,
+    ) -> Result<PlaylistItemMoveResponse, Error> {
+        self.post(Endpoint::PlaylistItemMove(playlist_mbid), token, data)
+            .await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/delete`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-delete)
+    pub async fn playlist_item_delete(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistItemDelete,
+    ) -> Result<PlaylistItemDeleteResponse, Error> {
+        self.post(Endpoint::PlaylistItemDelete(playlist_mbid), token, data)
+            .await
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/export/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-export-(service))
+    ///
+    /// Creates a copy of the playlist on the service, which must be linked to the account of the user.
+    pub async fn playlist_export(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        service: MusicService,
+    ) -> Result<PlaylistExportResponse, Error> {
+        self.post(Endpoint::PlaylistExport(playlist_mbid, service), token, ())
+            .await
+    }
+
+    /// Endpoint: [`playlist/export-jspf/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-export-jspf-(service))
+    ///
+    /// Like [`AsyncClient::playlist_export`], but exports the given playlist instead of one
+    /// that is stored on ListenBrainz.
+    pub async fn playlist_export_jspf(
+        &self,
+        token: &str,
+        service: MusicService,
+        playlist: &jspf::Playlist,
+    ) -> Result<PlaylistExportResponse, Error> {
+        self.post(Endpoint::PlaylistExportJspf(service), token, playlist)
+            .await
+    }
+
+    /// Endpoint: [`playlist/import/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-import-(service))
+    ///
+    /// Lists the playlists of the user on the service, which must be linked to the account of the user.
+    pub async fn playlist_import(
+        &self,
+        token: &str,
+        service: MusicService,
+    ) -> Result<PlaylistImportResponse, Error> {
+        let response = self
+            .send(
+                self.request(Method::Get, Endpoint::PlaylistImport(service))?
+                    .token(token),
+            )
+            .await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`playlist/spotify/{playlist_id}/tracks`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-spotify-(playlist_id)-tracks)
+    /// or [`playlist/apple_music/{playlist_id}/tracks`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-apple_music-(playlist_id)-tracks)
+    ///
+    /// Fetches the tracks of a playlist on the service, as returned by [`AsyncClient::playlist_import`],
+    /// and maps them to MusicBrainz recordings.
+    pub async fn playlist_service_tracks(
+        &self,
+        token: &str,
+        service: MusicService,
+        playlist_id: &str,
+    ) -> Result<PlaylistServiceTracksResponse, Error> {
+        let response = self
+            .send(
+                self.request(
+                    Method::Get,
+                    Endpoint::PlaylistServiceTracks(service, playlist_id),
+                )?
+                .token(token),
+            )
+            .await?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`stats/sitewide/artists`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-sitewide-artists)
+    pub async fn stats_sitewide_artists(
+        &self,
+        count: Option<u64>,
+        offset: Option<u64>,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsSitewideArtistsResponse>, Error> {
+        self.get_paged(Endpoint::StatsSitewideArtists, count, offset, range)
+            .await
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/listening-activity`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-listening-activity)
+    pub async fn stats_user_listening_activity(
+        &self,
+        user_name: &UserName,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsUserListeningActivityResponse>, Error> {
+        self.get_paged(
+            Endpoint::StatsUserListeningActivity(user_name),
+            None,
+            None,
+            range,
+        )
+        .await
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/daily-activity`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-daily-activity)
+    pub async fn stats_user_daily_activity(
+        &self,
+        user_name: &UserName,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsUserDailyActivityResponse>, Error> {
+        self.get_paged(
+            Endpoint::StatsUserDailyActivity(user_name),
+            None,
+            None,
+            range,
+        )
+        .await
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/recordings`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-recordings)
+    pub async fn stats_user_recordings(
+        &self,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsUserRecordingsResponse>, Error> {
+        self.get_paged(
+            Endpoint::StatsUserRecordings(user_name),
+            count,
+            offset,
+            range,
+        )
+        .await
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/artist-map`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-artist-map)
+    pub async fn stats_user_artist_map(
+        &self,
+        user_name: &UserName,
+        range: Option<StatsRange>,
+        force_recalculate: Option<bool>,
+    ) -> Result<Option<StatsUserArtistMapResponse>, Error> {
+        let mut request = self.request(Method::Get, Endpoint::StatsUserArtistMap(user_name))?;
+
+        if let Some(range) = range {
+            request = request.param("range", range.as_str());
+        }
+        if let Some(force_recalculate) = force_recalculate {
+            request = request.param("force_recalculate", force_recalculate);
+        }
+
+        self.send(request).await?.into_optional_response()
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/releases`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-releases)
+    pub async fn stats_user_releases(
+        &self,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsUserReleasesResponse>, Error> {
+        self.get_paged(Endpoint::StatsUserReleases(user_name), count, offset, range)
+            .await
+    }
+
+    /// Endpoint: [`stats/user/{user_name}/artists`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-artists)
+    pub async fn stats_user_artists(
+        &self,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsUserArtistsResponse>, Error> {
+        self.get_paged(Endpoint::StatsUserArtists(user_name), count, offset, range)
+            .await
+    }
+
+    /// Endpoint: [`GET /1/stats/release-group/(release_group_mbid)/listeners`](https://listenbrainz.readthedocs.io/en/latest/users/api/statistics.html#get--1-stats-release-group-(release_group_mbid)-listeners)
+    /// Get the top listeners for a release group, as well as getting the total number of listens for it
+    pub async fn stats_release_group_listeners(
+        &self,
+        release_group_mbid: &Mbid,
+        range: Option<StatsRange>,
+    ) -> Result<Option<StatsReleaseGroupListenersResponse>, Error> {
+        self.get_paged(
+            Endpoint::StatsReleaseGroupListeners(release_group_mbid),
+            None,
+            None,
+            range,
+        )
+        .await
+    }
+
+    /// Endpoint: [`metadata/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-recording-)
+    ///
+    /// Get the metadata of one or more recordings, with the additional metadata in `inc`.
+    pub async fn metadata_recording(
+        &self,
+        recording_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataRecordingResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataRecording)?
+            .param("recording_mbids", Mbid::join(recording_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`metadata/release_group`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-release_group-)
+    ///
+    /// Get the metadata of one or more release groups, with the additional metadata in `inc`.
+    pub async fn metadata_release_group(
+        &self,
+        release_group_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataReleaseGroupResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataReleaseGroup)?
+            .param("release_group_mbids", Mbid::join(release_group_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`metadata/artist`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-artist-)
+    ///
+    /// Get the metadata of one or more artists, with the additional metadata in `inc`.
+    pub async fn metadata_artist(
+        &self,
+        artist_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataArtistResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataArtist)?
+            .param("artist_mbids", Mbid::join(artist_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`metadata/lookup`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-lookup-)
+    ///
+    /// Look up a recording by the names of the artist, recording and (optionally) release.
+    /// If `metadata` is `Some`, the metadata of the recording is included as well,
+    /// with the given additional metadata.
+    pub async fn metadata_lookup(
+        &self,
+        artist_name: &str,
+        recording_name: &str,
+        release_name: Option<&str>,
+        metadata: Option<&[MetadataInclude]>,
+    ) -> Result<MetadataLookupResponse, Error> {
+        let mut request = self
+            .request(Method::Get, Endpoint::MetadataLookup)?
+            .param("artist_name", artist_name)
+            .param("recording_name", recording_name);
+
+        if let Some(release_name) = release_name {
+            request = request.param("release_name", release_name);
+        }
+        if let Some(inc) = metadata {
+            request = request
+                .param("metadata", true)
+                .param("inc", MetadataInclude::join(inc));
+        }
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`metadata/submit_manual_mapping`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#post--1-metadata-submit_manual_mapping-)
+    ///
+    /// Link a listened recording (by MSID) to a MusicBrainz recording, for listens that
+    /// were not mapped automatically or were mapped to the wrong recording.
+    pub async fn metadata_submit_manual_mapping<T: StrType>(
+        &self,
+        token: &str,
+        data: MetadataSubmitManualMapping<T>,
+    ) -> Result<MetadataSubmitManualMappingResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::MetadataSubmitManualMapping, token, data)
+            .await
+    }
+
+    /// Endpoint: [`metadata/get_manual_mapping`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-get_manual_mapping-)
+    ///
+    /// Get the manual mapping that the user of the token submitted for a recording MSID.
+    pub async fn metadata_get_manual_mapping(
+        &self,
+        token: &str,
+        recording_msid: &str,
+    ) -> Result<MetadataGetManualMappingResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataGetManualMapping)?
+            .token(token)
+            .param("recording_msid", recording_msid);
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`popularity/top-recordings-for-artist/{artist_mbid}`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-recordings-for-artist-(artist_mbid))
+    ///
+    /// Get the most listened recordings of an artist.
+    pub async fn popularity_top_recordings_for_artist(
+        &self,
+        artist_mbid: &Mbid,
+    ) -> Result<PopularityTopRecordingsForArtistResponse, Error> {
+        self.get(Endpoint::PopularityTopRecordingsForArtist(artist_mbid))
+            .await
+    }
+
+    /// Endpoint: [`popularity/top-release-groups-for-artist/{artist_mbid}`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-release-groups-for-artist-(artist_mbid))
+    ///
+    /// Get the most listened release groups of an artist.
+    pub async fn popularity_top_release_groups_for_artist(
+        &self,
+        artist_mbid: &Mbid,
+    ) -> Result<PopularityTopReleaseGroupsForArtistResponse, Error> {
+        self.get(Endpoint::PopularityTopReleaseGroupsForArtist(artist_mbid))
+            .await
+    }
+
+    /// Endpoint: [`popularity/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-recording)
+    ///
+    /// Get the total number of listens and listeners of one or more recordings.
+    pub async fn popularity_recording(
+        &self,
+        recording_mbids: &[Mbid],
+    ) -> Result<PopularityRecordingResponse, Error> {
+        self.post_mbids(
+            Endpoint::PopularityRecording,
+            "recording_mbids",
+            recording_mbids,
+        )
+        .await
+    }
+
+    /// Endpoint: [`popularity/artist`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-artist)
+    ///
+    /// Get the total number of listens and listeners of one or more artists.
+    pub async fn popularity_artist(
+        &self,
+        artist_mbids: &[Mbid],
+    ) -> Result<PopularityArtistResponse, Error> {
+        self.post_mbids(Endpoint::PopularityArtist, "artist_mbids", artist_mbids)
+            .await
+    }
+
+    /// Endpoint: [`popularity/release`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release)
+    ///
+    /// Get the total number of listens and listeners of one or more releases.
+    pub async fn popularity_release(
+        &self,
+        release_mbids: &[Mbid],
+    ) -> Result<PopularityReleaseResponse, Error> {
+        self.post_mbids(Endpoint::PopularityRelease, "release_mbids", release_mbids)
+            .await
+    }
+
+    /// Endpoint: [`popularity/release-group`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release-group)
+    ///
+    /// Get the total number of listens and listeners of one or more release groups.
+    pub async fn popularity_release_group(
+        &self,
+        release_group_mbids: &[Mbid],
+    ) -> Result<PopularityReleaseGroupResponse, Error> {
+        self.post_mbids(
+            Endpoint::PopularityReleaseGroup,
+            "release_group_mbids",
+            release_group_mbids,
+        )
+        .await
+    }
+
+    /// Endpoint: [`cf/recommendation/user/{user_name}/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-cf-recommendation-user-(user_name)-recording)
+    ///
+    /// Get the recordings recommended for a user by collaborative filtering.
+    /// Returns [`None`] if there are no recommendations for the user.
+    pub async fn cf_recommendation_user_recording(
+        &self,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Option<CfRecommendationUserRecordingResponse>, Error> {
+        self.get_paged(
+            Endpoint::CfRecommendationUserRecording(user_name),
+            count,
+            offset,
+            None,
+        )
+        .await
+    }
+
+    /// Endpoint: [`recommendation/feedback/submit`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-submit)
+    ///
+    /// Submit the rating of the user of the token for a recommended recording,
+    /// replacing any earlier rating.
+    pub async fn recommendation_feedback_submit<T: StrType>(
+        &self,
+        token: &str,
+        data: RecommendationFeedbackSubmit<T>,
+    ) -> Result<RecommendationFeedbackSubmitResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::RecommendationFeedbackSubmit, token, data)
+            .await
+    }
+
+    /// Endpoint: [`recommendation/feedback/delete`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-delete)
+    ///
+    /// Delete the rating of the user of the token for a recommended recording.
+    pub async fn recommendation_feedback_delete<T: StrType>(
+        &self,
+        token: &str,
+        data: RecommendationFeedbackDelete<T>,
+    ) -> Result<RecommendationFeedbackDeleteResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::RecommendationFeedbackDelete, token, data)
+            .await
+    }
+
+    /// Endpoint: [`recommendation/feedback/user/{user_name}`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name))
+    ///
+    /// Get the ratings of a user for recommended recordings, newest first,
+    /// optionally only those with the given rating.
+    pub async fn recommendation_feedback_user(
+        &self,
+        user_name: &UserName,
+        rating: Option<RecommendationFeedbackRating>,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<RecommendationFeedbackUserResponse, Error> {
+        let mut request =
+            self.request(Method::Get, Endpoint::RecommendationFeedbackUser(user_name))?;
+
+        if let Some(rating) = rating {
+            request = request.param("rating", rating);
+        }
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`recommendation/feedback/user/{user_name}/recordings`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name)-recordings)
+    ///
+    /// Get the ratings of a user for the given recommended recordings.
+    pub async fn recommendation_feedback_user_recordings(
+        &self,
+        user_name: &UserName,
+        recording_mbids: &[Mbid],
+    ) -> Result<RecommendationFeedbackUserRecordingsResponse, Error> {
+        let request = self
+            .request(
+                Method::Get,
+                Endpoint::RecommendationFeedbackUserRecordings(user_name),
+            )?
+            .param("mbids", Mbid::join(recording_mbids));
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`status/get-dump-info`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-status-get-dump-info)
+    pub async fn status_get_dump_info(
+        &self,
+        id: Option<i64>,
+    ) -> Result<StatusGetDumpInfoResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::StatusGetDumpInfo)?;
+
+        if let Some(id) = id {
+            request = request.param("id", id);
+        }
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`user/{user_name}/followers`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-followers)
+    pub async fn user_followers(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserFollowersResponse, Error> {
+        self.get(Endpoint::UserFollowers(user_name)).await
+    }
+
+    /// Endpoint: [`user/{user_name}/following`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-following)
+    pub async fn user_following(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserFollowingResponse, Error> {
+        self.get(Endpoint::UserFollowing(user_name)).await
+    }
+
+    /// Endpoint: [`user/{user_name}/unfollow`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-user-(user_name)-unfollow)
+    pub async fn user_unfollow(
+        &self,
+        token: &str,
+        user_name: &UserName,
+    ) -> Result<UserUnfollowResponse, Error> {
+        let request = self
+            .request(Method::Post, Endpoint::UserUnfollow(user_name))?
+            .token(token);
+
+        self.send(request).await?.into_response()
+    }
+
+    /// Endpoint: [`user/{user_name}/follow`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-user-(user_name)-follow)
+    pub async fn user_follow(
+        &self,
+        token: &str,
+        user_name: &UserName,
+    ) -> Result<UserFollowResponse, Error> {
+        let request = self
+            .request(Method::Post, Endpoint::UserFollow(user_name))?
+            .token(token);
+
+        self.send(request).await?.into_response()
+    }
+}
+
+impl Default for AsyncClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::ids::testing::user;
+    use crate::raw::transport::mock::MockTransport;
+    use crate::raw::transport::stub::StubServer;
+
+    const API_URL: &str = "http://localhost/1/";
+
+    #[tokio::test]
+    async fn maps_no_content_to_none() {
+        let transport = MockTransport::new(|_| Ok(HttpResponse::new(204, "")));
+        let client = AsyncClient::new_with_transport(API_URL, transport.clone());
+
+        let response = client
+            .stats_user_artists(&user("koen"), Some(5), Some(10), Some(StatsRange::Month))
+            .await
+            .unwrap();
+        assert!(response.is_none());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(
+            requests[0].url,
+            "http://localhost/1/stats/user/koen/artists"
+        );
+        assert_eq!(
+            requests[0].params,
+            [
+                ("count".to_string(), "5".to_string()),
+                ("offset".to_string(), "10".to_string()),
+                ("range".to_string(), "month".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_the_rate_limit_headers() {
+        let transport = MockTransport::new(|_| {
+            Ok(HttpResponse::new(200, r#"{"payload": {"count": 42}}"#)
+                .with_header("x-ratelimit-limit", "30")
+                .with_header("X-RateLimit-Remaining", "29")
+                .with_header("X-RateLimit-Reset-In", "10")
+                .with_header("X-RateLimit-Reset", "1700000000"))
+        });
+        let client = AsyncClient::new_with_transport(API_URL, transport);
+
+        let response = client.user_listen_count(&user("koen")).await.unwrap();
+        assert_eq!(response.payload.count, 42);
+        assert_eq!(response.attempts, 1);
+        assert_eq!(
+            response.rate_limit,
+            Some(RateLimit {
+                limit: 30,
+                remaining: 29,
+                reset_in: 10,
+                reset: 1700000000,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn maps_errors() {
+        let transport = MockTransport::new(|request| {
+            if request.url.ends_with("/missing/listen-count") {
+                Ok(HttpResponse::new(
+                    404,
+                    r#"{"code": 404, "error": "Cannot find user: missing"}"#,
+                ))
+            } else if request.url.ends_with("/koen/listen-count") {
+                Ok(HttpResponse::new(200, "not JSON"))
+            } else {
+                Err(Error::Http(Box::new(std::io::Error::other("offline"))))
+            }
+        });
+        let client = AsyncClient::new_with_transport(API_URL, transport.clone());
+
+        let error = client
+            .user_listen_count(&user("missing"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Api {
+                code: 404,
+                ref error,
+                attempts: 1,
+            } if error == "Cannot find user: missing"
+        ));
+
+        let error = client.user_listen_count(&user("koen")).await.unwrap_err();
+        assert!(matches!(error, Error::Json(_)));
+
+        let error = client.user_playing_now(&user("koen")).await.unwrap_err();
+        assert!(matches!(error, Error::Http(_)));
+
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn sends_requests_over_http_to_the_configured_api_url() {
+        let server = StubServer::start(200, r#"{"payload": {"count": 42}}"#);
+
+        let client = AsyncClient::new_with_url(server.url());
+        assert_eq!(client.api_url(), server.url());
+        let response = client.user_listen_count(&user("koen")).await.unwrap();
+        assert_eq!(response.payload.count, 42);
+        assert_eq!(response.rate_limit.unwrap().remaining, 29);
+
+        assert_eq!(server.requests(), ["GET /1/user/koen/listen-count"]);
+    }
+}
@@ -0,0 +1,346 @@
+use std::time::Duration;
+
+use attohttpc::ProxySettings;
+
+use super::client::API_ROOT_URL;
+#[cfg(feature = "keep-alive")]
+use super::transport::ReqwestBlockingTransport;
+use super::transport::{AttohttpcTransport, Transport, DEFAULT_USER_AGENT};
+use super::{Client, RetryPolicy};
+use crate::Error;
+
+/// Builder for a [`Client`] with custom settings.
+///
+/// Construct one with [`Client::builder`]. The connection settings (timeouts, proxy and
+/// `User-Agent`) apply to the default [`AttohttpcTransport`], or to the transport that keeps
+/// connections alive (see `ClientBuilder::keep_alive`); they are ignored when a
+/// custom transport is set with [`ClientBuilder::transport`].
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use listenbrainz::raw::Client;
+/// let client = Client::builder()
+///     .user_agent("my-scrobbler/1.0 (me@example.com)")
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    api_url: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    throttling: bool,
+    retry_policy: RetryPolicy,
+    #[cfg(feature = "keep-alive")]
+    keep_alive: bool,
+    transport: Option<Box<dyn Transport>>,
+}
+
+impl ClientBuilder {
+    /// Construct a new builder with the default settings.
+    pub fn new() -> Self {
+        Self {
+            api_url: API_ROOT_URL.to_string(),
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            proxy: None,
+            user_agent: None,
+            throttling: false,
+            retry_policy: RetryPolicy::default(),
+            #[cfg(feature = "keep-alive")]
+            keep_alive: false,
+            transport: None,
+        }
+    }
+
+    /// Set the API URL. Defaults to `https://api.listenbrainz.org/1/`.
+    pub fn api_url(mut self, url: impl ToString) -> Self {
+        self.api_url = url.to_string();
+        self
+    }
+
+    /// Set the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for every read operation on a connection.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for a request as a whole, from connecting to reading the response.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send all requests through the HTTP proxy at the given URL.
+    ///
+    /// By default, the proxy is taken from the `HTTP_PROXY`, `HTTPS_PROXY`
+    /// and `NO_PROXY` environment variables.
+    pub fn proxy(mut self, url: impl ToString) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request.
+    ///
+    /// MetaBrainz asks API users to send a descriptive `User-Agent` that identifies
+    /// the application and provides contact information.
+    /// Defaults to [`DEFAULT_USER_AGENT`].
+    pub fn user_agent(mut self, user_agent: impl ToString) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Enable or disable rate limit aware throttling.
+    /// See [`Client::set_throttling`] for details.
+    pub fn throttling(mut self, enabled: bool) -> Self {
+        self.throttling = enabled;
+        self
+    }
+
+    /// Set the policy for retrying requests that failed because of transient problems.
+    /// See [`RetryPolicy`] for details.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Enable or disable keeping connections alive between requests.
+    ///
+    /// `attohttpc` opens a new connection for every request. When enabled, requests are
+    /// performed by a [`ReqwestBlockingTransport`] instead, which reuses its connections.
+    /// The read timeout is not supported by this transport and is ignored.
+    ///
+    /// Requires the `keep-alive` feature.
+    #[cfg(feature = "keep-alive")]
+    pub fn keep_alive(mut self, enabled: bool) -> Self {
+        self.keep_alive = enabled;
+        self
+    }
+
+    /// Perform requests using the given [`Transport`] instead of [`AttohttpcTransport`].
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// Returns [`Error::Http`] if the proxy URL or the `User-Agent` is invalid.
+    pub fn build(mut self) -> Result<Client, Error> {
+        let transport: Box<dyn Transport> = match self.transport.take() {
+            Some(transport) => transport,
+            #[cfg(feature = "keep-alive")]
+            None if self.keep_alive => Box::new(ReqwestBlockingTransport::with_client(
+                self.reqwest_client()?,
+            )),
+            None => Box::new(AttohttpcTransport::with_session(self.session()?)),
+        };
+
+        let mut client = Client::new_with_transport(self.api_url, transport);
+        client.set_throttling(self.throttling);
+        client.set_retry_policy(self.retry_policy);
+
+        Ok(client)
+    }
+
+    /// Create the session for the default transport from the connection settings.
+    fn session(&self) -> Result<attohttpc::Session, Error> {
+        let mut session = attohttpc::Session::new();
+
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        session.try_header(attohttpc::header::USER_AGENT, user_agent)?;
+
+        if let Some(timeout) = self.connect_timeout {
+            session.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            session.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            session.timeout(timeout);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            let url = url::Url::parse(proxy).map_err(|error| Error::Http(Box::new(error)))?;
+            session.proxy_settings(
+                ProxySettings::builder()
+                    .http_proxy(url.clone())
+                    .https_proxy(url)
+                    .build(),
+            );
+        }
+
+        Ok(session)
+    }
+
+    /// Create the client for the transport that keeps connections alive
+    /// from the connection settings.
+    #[cfg(feature = "keep-alive")]
+    fn reqwest_client(&self) -> Result<reqwest::blocking::Client, Error> {
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        let mut builder = reqwest::blocking::Client::builder()
+            .user_agent(user_agent)
+            .timeout(self.timeout);
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::raw::ids::testing::user;
+    use crate::raw::transport::stub::StubServer;
+
+    const LISTEN_COUNT: &str = r#"{"payload": {"count": 42}}"#;
+
+    /// A builder for every kind of default transport.
+    fn builders() -> Vec<ClientBuilder> {
+        vec![
+            ClientBuilder::new(),
+            #[cfg(feature = "keep-alive")]
+            ClientBuilder::new().keep_alive(true),
+        ]
+    }
+
+    #[test]
+    fn builds_clients_with_the_api_url_and_user_agent() {
+        for (builder, custom) in builders().into_iter().zip(builders()) {
+            let server = StubServer::start(200, LISTEN_COUNT);
+
+            let client = builder.api_url(server.url()).build().unwrap();
+            assert_eq!(client.api_url(), server.url());
+            client.user_listen_count(&user("koen")).unwrap();
+
+            let client = custom
+                .api_url(server.url())
+                .user_agent("my-scrobbler/1.0 (me@example.com)")
+                .build()
+                .unwrap();
+            client.user_listen_count(&user("koen")).unwrap();
+
+            let requests = server.received();
+            assert_eq!(requests[0].line, "GET /1/user/koen/listen-count");
+            assert_eq!(requests[0].header("User-Agent"), Some(DEFAULT_USER_AGENT));
+            assert_eq!(
+                requests[1].header("User-Agent"),
+                Some("my-scrobbler/1.0 (me@example.com)")
+            );
+        }
+    }
+
+    #[test]
+    fn sends_requests_through_the_proxy() {
+        for builder in builders() {
+            let proxy = StubServer::start(200, LISTEN_COUNT);
+
+            let client = builder
+                .api_url("http://listenbrainz.example/1/")
+                .proxy(proxy.url())
+                .build()
+                .unwrap();
+            client.user_listen_count(&user("koen")).unwrap();
+
+            assert_eq!(
+                proxy.requests(),
+                ["GET http://listenbrainz.example/1/user/koen/listen-count"]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_proxies_and_user_agents() {
+        for builder in builders() {
+            let error = builder.proxy("not a proxy URL").build().unwrap_err();
+            assert!(matches!(error, Error::Http(_)), "{error:?}");
+        }
+        for builder in builders() {
+            let error = builder.user_agent("line\nbreak").build().unwrap_err();
+            assert!(matches!(error, Error::Http(_)), "{error:?}");
+        }
+    }
+
+    #[test]
+    fn times_out() {
+        // Connections are accepted by the OS, but never answered
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/1/", listener.local_addr().unwrap());
+
+        for builder in builders() {
+            let client = builder
+                .api_url(&url)
+                .timeout(Duration::from_millis(200))
+                .build()
+                .unwrap();
+
+            let start = Instant::now();
+            let error = client.user_listen_count(&user("koen")).unwrap_err();
+            assert!(matches!(error, Error::Http(_)), "{error:?}");
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+
+        let client = Client::builder()
+            .api_url(&url)
+            .read_timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let error = client.user_listen_count(&user("koen")).unwrap_err();
+        assert!(matches!(error, Error::Http(_)), "{error:?}");
+    }
+
+    #[test]
+    fn opens_a_connection_for_every_request_by_default() {
+        let server = StubServer::start(200, LISTEN_COUNT);
+        let client = Client::builder().api_url(server.url()).build().unwrap();
+        for _ in 0..3 {
+            client.user_listen_count(&user("koen")).unwrap();
+        }
+        assert_eq!(server.connections(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "keep-alive")]
+    fn reuses_connections_with_keep_alive() {
+        let server = StubServer::start(200, LISTEN_COUNT);
+        let client = Client::builder()
+            .api_url(server.url())
+            .keep_alive(true)
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            client.user_listen_count(&user("koen")).unwrap();
+        }
+        assert_eq!(server.connections(), 1);
+        assert_eq!(server.requests().len(), 3);
+    }
+}
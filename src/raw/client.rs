@@ -1,12 +1,24 @@
-use attohttpc::header::AUTHORIZATION;
+use std::thread;
+
 use serde::Serialize;
 
+use super::builder::ClientBuilder;
 use super::endpoint::Endpoint;
+use super::ids::{Mbid, UserName};
+use super::jspf;
+use super::pagination::{OffsetIter, Page, UserListensIter};
 use super::request::*;
 use super::response::*;
+use super::retry::RetryPolicy;
+use super::throttle::Throttle;
+use super::transport::{AttohttpcTransport, HttpRequest, Method, Transport};
 use crate::Error;
 
-const API_ROOT_URL: &str = "https://api.listenbrainz.org/1/";
+pub(super) const API_ROOT_URL: &str = "https://api.listenbrainz.org/1/";
+
+/// How many times a request is retried after a `429 Too Many Requests` response
+/// when throttling is enabled.
+const MAX_RATE_LIMITED_RETRIES: u32 = 3;
 
 /// Low-level client that directly wraps the ListenBrainz HTTP API.
 ///
@@ -19,9 +31,33 @@ const API_ROOT_URL: &str = "https://api.listenbrainz.org/1/";
 /// - [`Error::Api`]: the API returned a non-`2XX` status.
 /// - [`Error::Json`]: the request or response data could not be converted from or into JSON.
 /// - [`Error::Http`]: there was some other HTTP error while interacting with the API.
+///
+/// # Transport
+///
+/// Requests are performed by a [`Transport`], which is [`AttohttpcTransport`] by default.
+/// Use [`Client::new_with_transport`] to perform requests in a different way, or
+/// [`Client::builder`] to configure timeouts, a proxy or the `User-Agent`.
+/// See the [`transport`](super::transport) module for more details.
+///
+/// # Rate limiting
+///
+/// Every response carries the [rate limiting](RateLimit) information of the API.
+/// By default, the client does not act on it. With [`Client::set_throttling`],
+/// the client waits until the rate limit resets before sending a request that
+/// would exceed it, and retries requests that were rejected with `429 Too Many Requests`.
+///
+/// # Retries
+///
+/// By default, failed requests are not retried. With [`Client::set_retry_policy`],
+/// requests that failed because of transient problems (such as a `502 Bad Gateway`
+/// response) are retried according to a [`RetryPolicy`]. The number of attempts made
+/// for a request is reported in the `attempts` field of its response, or of [`Error::Api`].
 #[derive(Debug)]
 pub struct Client {
     api_root_url: String,
+    transport: Box<dyn Transport>,
+    throttle: Option<Throttle>,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -30,10 +66,25 @@ impl Client {
         Self::new_with_url(API_ROOT_URL)
     }
 
+    /// Construct a builder to configure a new client.
+    /// See [`ClientBuilder`] for the available settings.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
     /// Construct a new client with a custom API URL.
     pub fn new_with_url(url: impl ToString) -> Self {
+        Self::new_with_transport(url, AttohttpcTransport::new())
+    }
+
+    /// Construct a new client with a custom API URL that performs its requests
+    /// using the given [`Transport`].
+    pub fn new_with_transport(url: impl ToString, transport: impl Transport + 'static) -> Self {
         Self {
             api_root_url: url.to_string(),
+            transport: Box::new(transport),
+            throttle: None,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
@@ -42,28 +93,99 @@ impl Client {
         &self.api_root_url
     }
 
+    /// Enable or disable rate limit aware throttling.
+    ///
+    /// When enabled, the client keeps track of the last [`RateLimit`] reported by the API.
+    /// Before sending a request that would exceed the rate limit, it blocks the calling
+    /// thread until the rate limit resets. Requests that are rejected with
+    /// `429 Too Many Requests` are retried (up to 3 times) once the rate limit has reset.
+    pub fn set_throttling(&mut self, enabled: bool) {
+        self.throttle = enabled.then(Throttle::default);
+    }
+
+    /// Check if rate limit aware throttling is enabled.
+    pub fn is_throttling(&self) -> bool {
+        self.throttle.is_some()
+    }
+
+    /// Set the policy for retrying requests that failed because of transient problems.
+    /// See [`RetryPolicy`] for details.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Get the policy for retrying requests.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Helper method to build a request against an endpoint of the configured API.
+    ///
+    /// Every request of this client is built here, so that it always uses the API URL
+    /// of this client, and invalid user names or MBIDs are rejected before sending.
+    fn request(&self, method: Method, endpoint: Endpoint) -> Result<HttpRequest, Error> {
+        endpoint.validate()?;
+        Ok(HttpRequest::new(
+            method,
+            format!("{}{}", self.api_root_url, endpoint),
+        ))
+    }
+
+    /// Helper method to send a request using the transport of this client,
+    /// applying throttling and retries.
+    fn send(&self, request: HttpRequest) -> Result<Attempted, Error> {
+        let idempotent = request.method == Method::Get;
+
+        let mut attempts = 0;
+        let mut rate_limited_retries = 0;
+        loop {
+            if let Some(throttle) = &self.throttle {
+                throttle.wait();
+            }
+
+            attempts += 1;
+            let result = self.transport.send(&request);
+
+            if let (Some(throttle), Ok(response)) = (&self.throttle, &result) {
+                throttle.update(response);
+
+                // The throttle already waited for the rate limit to reset
+                if response.status == 429 && rate_limited_retries < MAX_RATE_LIMITED_RETRIES {
+                    rate_limited_retries += 1;
+                    continue;
+                }
+            }
+
+            if attempts < self.retry_policy.max_attempts
+                && self.retry_policy.should_retry(&result, idempotent)
+            {
+                thread::sleep(self.retry_policy.backoff(attempts));
+                continue;
+            }
+
+            return result.map(|response| Attempted { response, attempts });
+        }
+    }
+
     /// Helper method to perform a GET request against an endpoint
     /// without any query parameters.
     fn get<R: ResponseType>(&self, endpoint: Endpoint) -> Result<R, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, endpoint);
-
-        let response = attohttpc::get(endpoint).send()?;
+        let response = self.send(self.request(Method::Get, endpoint)?)?;
 
-        R::from_response(response)
+        response.into_response()
     }
 
-    /// Helper method to perform a GET request against (most) statistics
-    /// endpoints that share common query parameters.
-    fn get_stats<R: ResponseType>(
+    /// Helper method to perform a GET request against an endpoint that is paginated with
+    /// `count` and `offset`, such as most statistics endpoints, optionally with a statistics
+    /// `range`. Returns `None` if the API has no data (`204 No Content`).
+    fn get_paged<R: ResponseType>(
         &self,
         endpoint: Endpoint,
         count: Option<u64>,
         offset: Option<u64>,
-        range: Option<&str>,
+        range: Option<StatsRange>,
     ) -> Result<Option<R>, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, endpoint);
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, endpoint)?;
 
         if let Some(count) = count {
             request = request.param("count", count);
@@ -72,17 +194,30 @@ impl Client {
             request = request.param("offset", offset);
         }
         if let Some(range) = range {
-            request = request.param("range", range);
+            request = request.param("range", range.as_str());
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        // API returns 204 and an empty document if there are no statistics
-        if response.status() == 204 {
-            Ok(None)
-        } else {
-            R::from_response(response).map(Some)
-        }
+        response.into_optional_response()
+    }
+
+    /// Helper method to perform an unauthenticated POST request against the bulk lookup
+    /// endpoints, which expect a list of MBIDs as `{"<key>": [...]}`.
+    fn post_mbids<R: ResponseType>(
+        &self,
+        endpoint: Endpoint,
+        key: &str,
+        mbids: &[Mbid],
+    ) -> Result<R, Error> {
+        let mbids: Vec<&str> = mbids.iter().map(Mbid::as_str).collect();
+        let request = self
+            .request(Method::Post, endpoint)?
+            .json(&serde_json::json!({ key: mbids }))?;
+
+        let response = self.send(request)?;
+
+        response.into_response()
     }
 
     /// Helper method to perform a POST request against an endpoint
@@ -92,14 +227,14 @@ impl Client {
         D: Serialize,
         R: ResponseType,
     {
-        let endpoint = format!("{}{}", self.api_root_url, endpoint);
+        let request = self
+            .request(Method::Post, endpoint)?
+            .token(token)
+            .json(&data)?;
 
-        let response = attohttpc::post(endpoint)
-            .header(AUTHORIZATION, format!("Token {token}"))
-            .json(&data)?
-            .send()?;
+        let response = self.send(request)?;
 
-        R::from_response(response)
+        response.into_response()
     }
 
     /// Endpoint: [`submit-listens`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-submit-listens)
@@ -113,13 +248,12 @@ impl Client {
 
     /// Endpoint: [`validate-token`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-validate-token)
     pub fn validate_token(&self, token: &str) -> Result<ValidateTokenResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::ValidateToken);
-
-        let response = attohttpc::get(endpoint)
-            .header(AUTHORIZATION, format!("Token {token}"))
-            .send()?;
+        let response = self.send(
+            self.request(Method::Get, Endpoint::ValidateToken)?
+                .token(token),
+        )?;
 
-        ResponseType::from_response(response)
+        response.into_response()
     }
 
     /// Endpoint: [`delete-listen`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-delete-listen)
@@ -135,20 +269,15 @@ impl Client {
     pub fn user_playlists_collaborator(
         &self,
         token: Option<&str>,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
     ) -> Result<UserPlaylistsCollaboratorResponse, Error> {
-        let endpoint = format!(
-            "{}{}",
-            self.api_root_url,
-            Endpoint::UserPlaylistsCollaborator(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request =
+            self.request(Method::Get, Endpoint::UserPlaylistsCollaborator(user_name))?;
 
         if let Some(token) = token {
-            request = request.header("Authorization", format!("Token {}", token));
+            request = request.token(token);
         }
         if let Some(count) = count {
             request = request.param("count", count);
@@ -157,29 +286,41 @@ impl Client {
             request = request.param("offset", offset);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        ResponseType::from_response(response)
+        response.into_response()
+    }
+
+    /// Iterate over all playlists returned by [`Client::user_playlists_collaborator`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn user_playlists_collaborator_iter<'a>(
+        &'a self,
+        token: Option<&'a str>,
+        user_name: &'a UserName,
+    ) -> OffsetIter<'a, jspf::Playlist> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.user_playlists_collaborator(token, user_name, count, Some(offset))?;
+            Ok(Some(Page {
+                items: response.playlists,
+                total: Some(response.playlist_count),
+            }))
+        })
     }
 
     /// Endpoint: [`user/{user_name}/playlists/createdfor`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(playlist_user_name)-playlists-createdfor)
     pub fn user_playlists_created_for(
         &self,
         token: Option<&str>,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
     ) -> Result<UserPlaylistsCollaboratorResponse, Error> {
-        let endpoint = format!(
-            "{}{}",
-            self.api_root_url,
-            Endpoint::UserPlaylistsCreatedFor(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request =
+            self.request(Method::Get, Endpoint::UserPlaylistsCreatedFor(user_name))?;
 
         if let Some(token) = token {
-            request = request.header("Authorization", format!("Token {}", token));
+            request = request.token(token);
         }
         if let Some(count) = count {
             request = request.param("count", count);
@@ -188,31 +329,54 @@ impl Client {
             request = request.param("offset", offset);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
 
-        ResponseType::from_response(response)
+    /// Iterate over all playlists returned by [`Client::user_playlists_created_for`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn user_playlists_created_for_iter<'a>(
+        &'a self,
+        token: Option<&'a str>,
+        user_name: &'a UserName,
+    ) -> OffsetIter<'a, jspf::Playlist> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.user_playlists_created_for(token, user_name, count, Some(offset))?;
+            Ok(Some(Page {
+                items: response.playlists,
+                total: Some(response.playlist_count),
+            }))
+        })
     }
 
     /// Endpoint: [`user/{user_name}/similar-users`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-similar-users)
-    pub fn user_similar_users(&self, user_name: &str) -> Result<UserSimilarUsersResponse, Error> {
+    pub fn user_similar_users(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserSimilarUsersResponse, Error> {
         self.get(Endpoint::UserSimilarUsers(user_name))
     }
 
     /// Endpoint: [`user/{user_name}/listen-count`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-listen-count)
-    pub fn user_listen_count(&self, user_name: &str) -> Result<UserListenCountResponse, Error> {
+    pub fn user_listen_count(
+        &self,
+        user_name: &UserName,
+    ) -> Result<UserListenCountResponse, Error> {
         self.get(Endpoint::UserListenCount(user_name))
     }
 
     /// Endpoint: [`user/{user_name}/playing-now`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-playing-now)
-    pub fn user_playing_now(&self, user_name: &str) -> Result<UserPlayingNowResponse, Error> {
+    pub fn user_playing_now(&self, user_name: &UserName) -> Result<UserPlayingNowResponse, Error> {
         self.get(Endpoint::UserPlayingNow(user_name))
     }
 
     /// Endpoint: [`user/{user_name}/similar-to/{other_user_name}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-similar-to-(other_user_name))
     pub fn user_similar_to(
         &self,
-        user_name: &str,
-        other_user_name: &str,
+        user_name: &UserName,
+        other_user_name: &UserName,
     ) -> Result<UserSimilarToResponse, Error> {
         self.get(Endpoint::UserSimilarTo(user_name, other_user_name))
     }
@@ -221,20 +385,14 @@ impl Client {
     pub fn user_playlists(
         &self,
         token: Option<&str>,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
     ) -> Result<UserPlaylistsResponse, Error> {
-        let endpoint = format!(
-            "{}{}",
-            self.api_root_url,
-            Endpoint::UserPlaylists(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, Endpoint::UserPlaylists(user_name))?;
 
         if let Some(token) = token {
-            request = request.header("Authorization", format!("Token {}", token));
+            request = request.token(token);
         }
         if let Some(count) = count {
             request = request.param("count", count);
@@ -243,22 +401,36 @@ impl Client {
             request = request.param("offset", offset);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        ResponseType::from_response(response)
+        response.into_response()
+    }
+
+    /// Iterate over all playlists returned by [`Client::user_playlists`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn user_playlists_iter<'a>(
+        &'a self,
+        token: Option<&'a str>,
+        user_name: &'a UserName,
+    ) -> OffsetIter<'a, jspf::Playlist> {
+        OffsetIter::new(move |count, offset| {
+            let response = self.user_playlists(token, user_name, count, Some(offset))?;
+            Ok(Some(Page {
+                items: response.playlists,
+                total: Some(response.playlist_count),
+            }))
+        })
     }
 
     /// Endpoint: [`user/{user_name}/listens`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-listens)
     pub fn user_listens(
         &self,
-        user_name: &str,
+        user_name: &UserName,
         min_ts: Option<i64>,
         max_ts: Option<i64>,
         count: Option<u64>,
     ) -> Result<UserListensResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::UserListens(user_name));
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, Endpoint::UserListens(user_name))?;
 
         if let Some(min_ts) = min_ts {
             request = request.param("min_ts", min_ts);
@@ -270,27 +442,68 @@ impl Client {
             request = request.param("count", count);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        ResponseType::from_response(response)
+        response.into_response()
     }
 
-    /// Endpoint: [`latest-import`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-latest-import) (`GET`)
-    pub fn get_latest_import(&self, user_name: &str) -> Result<GetLatestImportResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::LatestImport);
-
-        let response = attohttpc::get(endpoint)
-            .param("user_name", user_name)
-            .send()?;
+    /// Iterate over all listens of a user, newest first, fetching pages with
+    /// [`Client::user_listens`] as needed. See [`UserListensIter`] for details.
+    pub fn user_listens_iter(&self, user_name: &UserName) -> UserListensIter<'_> {
+        UserListensIter::new(self, user_name)
+    }
 
-        ResponseType::from_response(response)
+    /// Endpoint: [`latest-import`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-latest-import) (`GET`)
+    pub fn get_latest_import(
+        &self,
+        user_name: &UserName,
+    ) -> Result<GetLatestImportResponse, Error> {
+        let response = self.send(
+            self.request(Method::Get, Endpoint::LatestImport)?
+                .param("user_name", user_name),
+        )?;
+
+        response.into_response()
     }
 
     /// Endpoint:
     /// [`playlist`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid))
     /// (`GET`)
-    pub fn get_playlist(&self, playlist: &str) -> Result<GetPlaylistResponse, Error> {
-        self.get(Endpoint::Playlist(playlist))
+    ///
+    /// A token is required to fetch private playlists.
+    pub fn get_playlist(
+        &self,
+        token: Option<&str>,
+        playlist: &Mbid,
+    ) -> Result<GetPlaylistResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::Playlist(playlist))?;
+
+        if let Some(token) = token {
+            request = request.token(token);
+        }
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/xspf`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid)-xspf)
+    ///
+    /// A token is required to fetch private playlists.
+    pub fn get_playlist_xspf(
+        &self,
+        token: Option<&str>,
+        playlist: &Mbid,
+    ) -> Result<GetPlaylistXspfResponse, Error> {
+        let mut request = self.request(Method::Get, Endpoint::PlaylistXspf(playlist))?;
+
+        if let Some(token) = token {
+            request = request.token(token);
+        }
+
+        let response = self.send(request)?;
+
+        response.into_response()
     }
 
     /// Endpoint: [`latest-import`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-latest-import) (`POST`)
@@ -315,7 +528,7 @@ impl Client {
     pub fn playlist_delete(
         &self,
         token: &str,
-        playlist_mbid: &str,
+        playlist_mbid: &Mbid,
     ) -> Result<PlaylistDeleteResponse, Error> {
         self.post(Endpoint::PlaylistDelete(playlist_mbid), token, ())
     }
@@ -324,86 +537,228 @@ impl Client {
     pub fn playlist_copy(
         &self,
         token: &str,
-        playlist_mbid: &str,
+        playlist_mbid: &Mbid,
     ) -> Result<PlaylistCopyResponse, Error> {
         self.post(Endpoint::PlaylistCopy(playlist_mbid), token, ())
     }
 
+    /// Endpoint: [`playlist/search`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-playlist-search)
+    ///
+    /// Searches public playlists by name and description.
+    pub fn playlist_search(
+        &self,
+        query: &str,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<PlaylistSearchResponse, Error> {
+        let mut request = self
+            .request(Method::Get, Endpoint::PlaylistSearch)?
+            .param("query", query);
+
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Iterate over all playlists returned by [`Client::playlist_search`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn playlist_search_iter<'a>(&'a self, query: &'a str) -> OffsetIter<'a, jspf::Playlist> {
+        OffsetIter::new(move |count, offset| {
+            let response = self.playlist_search(query, count, Some(offset))?;
+            Ok(Some(Page {
+                items: response.playlists,
+                total: Some(response.playlist_count),
+            }))
+        })
+    }
+
+    /// Endpoint: [`playlist/edit/{playlist_mbid}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-edit-(playlist_mbid))
+    pub fn playlist_edit(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistEdit,
+    ) -> Result<PlaylistEditResponse, Error> {
+        self.post(Endpoint::PlaylistEdit(playlist_mbid), token, data)
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/add`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-add)
+    /// or [`playlist/{playlist_mbid}/item/add/{offset}`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-add-(int-offset))
+    ///
+    /// Without an `offset`, the tracks are appended to the end of the playlist.
+    pub fn playlist_item_add(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        offset: Option<u64>,
+        data: PlaylistItemAdd,
+    ) -> Result<PlaylistItemAddResponse, Error> {
+        self.post(
+            Endpoint::PlaylistItemAdd(playlist_mbid, offset),
+            token,
+            data,
+        )
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/move`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-move)
+    pub fn playlist_item_move(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistItemMove,
+    ) -> Result<PlaylistItemMoveResponse, Error> {
+        self.post(Endpoint::PlaylistItemMove(playlist_mbid), token, data)
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/item/delete`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-playlist-(playlist_mbid)-item-delete)
+    pub fn playlist_item_delete(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        data: PlaylistItemDelete,
+    ) -> Result<PlaylistItemDeleteResponse, Error> {
+        self.post(Endpoint::PlaylistItemDelete(playlist_mbid), token, data)
+    }
+
+    /// Endpoint: [`playlist/{playlist_mbid}/export/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-export-(service))
+    ///
+    /// Creates a copy of the playlist on the service, which must be linked to the account of the user.
+    pub fn playlist_export(
+        &self,
+        token: &str,
+        playlist_mbid: &Mbid,
+        service: MusicService,
+    ) -> Result<PlaylistExportResponse, Error> {
+        self.post(Endpoint::PlaylistExport(playlist_mbid, service), token, ())
+    }
+
+    /// Endpoint: [`playlist/export-jspf/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-export-jspf-(service))
+    ///
+    /// Like [`Client::playlist_export`], but exports the given playlist instead of one
+    /// that is stored on ListenBrainz.
+    pub fn playlist_export_jspf(
+        &self,
+        token: &str,
+        service: MusicService,
+        playlist: &jspf::Playlist,
+    ) -> Result<PlaylistExportResponse, Error> {
+        self.post(Endpoint::PlaylistExportJspf(service), token, playlist)
+    }
+
+    /// Endpoint: [`playlist/import/{service}`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-import-(service))
+    ///
+    /// Lists the playlists of the user on the service, which must be linked to the account of the user.
+    pub fn playlist_import(
+        &self,
+        token: &str,
+        service: MusicService,
+    ) -> Result<PlaylistImportResponse, Error> {
+        let response = self.send(
+            self.request(Method::Get, Endpoint::PlaylistImport(service))?
+                .token(token),
+        )?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`playlist/spotify/{playlist_id}/tracks`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-spotify-(playlist_id)-tracks)
+    /// or [`playlist/apple_music/{playlist_id}/tracks`](https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-apple_music-(playlist_id)-tracks)
+    ///
+    /// Fetches the tracks of a playlist on the service, as returned by [`Client::playlist_import`],
+    /// and maps them to MusicBrainz recordings.
+    pub fn playlist_service_tracks(
+        &self,
+        token: &str,
+        service: MusicService,
+        playlist_id: &str,
+    ) -> Result<PlaylistServiceTracksResponse, Error> {
+        let response = self.send(
+            self.request(
+                Method::Get,
+                Endpoint::PlaylistServiceTracks(service, playlist_id),
+            )?
+            .token(token),
+        )?;
+
+        response.into_response()
+    }
+
     /// Endpoint: [`stats/sitewide/artists`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-sitewide-artists)
     pub fn stats_sitewide_artists(
         &self,
         count: Option<u64>,
         offset: Option<u64>,
-        range: Option<&str>,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsSitewideArtistsResponse>, Error> {
-        self.get_stats(Endpoint::StatsSitewideArtists, count, offset, range)
+        self.get_paged(Endpoint::StatsSitewideArtists, count, offset, range)
+    }
+
+    /// Iterate over all statistics returned by [`Client::stats_sitewide_artists`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn stats_sitewide_artists_iter<'a>(
+        &'a self,
+        range: Option<StatsRange>,
+    ) -> OffsetIter<'a, StatsSitewideArtistsArtist> {
+        OffsetIter::new(move |count, offset| {
+            let response = self.stats_sitewide_artists(count, Some(offset), range.clone())?;
+            Ok(response.map(|response| Page {
+                items: response.payload.artists,
+                total: None,
+            }))
+        })
     }
 
     /// Endpoint: [`stats/user/{user_name}/listening-activity`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-listening-activity)
     pub fn stats_user_listening_activity(
         &self,
-        user_name: &str,
-        range: Option<&str>,
+        user_name: &UserName,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsUserListeningActivityResponse>, Error> {
-        let endpoint = format!(
-            "{}{}",
-            API_ROOT_URL,
-            Endpoint::StatsUserListeningActivity(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request =
+            self.request(Method::Get, Endpoint::StatsUserListeningActivity(user_name))?;
 
         if let Some(range) = range {
-            request = request.param("range", range);
+            request = request.param("range", range.as_str());
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        // API returns 204 and an empty document if there are no statistics
-        if response.status() == 204 {
-            Ok(None)
-        } else {
-            ResponseType::from_response(response).map(Some)
-        }
+        response.into_optional_response()
     }
 
     /// Endpoint: [`stats/user/{user_name}/daily-activity`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-daily-activity)
     pub fn stats_user_daily_activity(
         &self,
-        user_name: &str,
-        range: Option<&str>,
+        user_name: &UserName,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsUserDailyActivityResponse>, Error> {
-        let endpoint = format!(
-            "{}{}",
-            API_ROOT_URL,
-            Endpoint::StatsUserDailyActivity(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, Endpoint::StatsUserDailyActivity(user_name))?;
 
         if let Some(range) = range {
-            request = request.param("range", range);
+            request = request.param("range", range.as_str());
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        // API returns 204 and an empty document if there are no statistics
-        if response.status() == 204 {
-            Ok(None)
-        } else {
-            ResponseType::from_response(response).map(Some)
-        }
+        response.into_optional_response()
     }
 
     /// Endpoint: [`stats/user/{user_name}/recordings`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-recordings)
     pub fn stats_user_recordings(
         &self,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
-        range: Option<&str>,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsUserRecordingsResponse>, Error> {
-        self.get_stats(
+        self.get_paged(
             Endpoint::StatsUserRecordings(user_name),
             count,
             offset,
@@ -411,68 +766,108 @@ impl Client {
         )
     }
 
+    /// Iterate over all statistics returned by [`Client::stats_user_recordings`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn stats_user_recordings_iter<'a>(
+        &'a self,
+        user_name: &'a UserName,
+        range: Option<StatsRange>,
+    ) -> OffsetIter<'a, StatsUserRecordingsRecording> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.stats_user_recordings(user_name, count, Some(offset), range.clone())?;
+            Ok(response.map(|response| Page {
+                items: response.payload.recordings,
+                total: Some(response.payload.total_recording_count),
+            }))
+        })
+    }
+
     /// Endpoint: [`stats/user/{user_name}/artist-map`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-artist-map)
     pub fn stats_user_artist_map(
         &self,
-        user_name: &str,
-        range: Option<&str>,
+        user_name: &UserName,
+        range: Option<StatsRange>,
         force_recalculate: Option<bool>,
     ) -> Result<Option<StatsUserArtistMapResponse>, Error> {
-        let endpoint = format!(
-            "{}{}",
-            API_ROOT_URL,
-            Endpoint::StatsUserArtistMap(user_name)
-        );
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, Endpoint::StatsUserArtistMap(user_name))?;
 
         if let Some(range) = range {
-            request = request.param("range", range);
+            request = request.param("range", range.as_str());
         }
         if let Some(force_recalculate) = force_recalculate {
             request = request.param("force_recalculate", force_recalculate);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        // API returns 204 and an empty document if there are no statistics
-        if response.status() == 204 {
-            Ok(None)
-        } else {
-            ResponseType::from_response(response).map(Some)
-        }
+        response.into_optional_response()
     }
 
     /// Endpoint: [`stats/user/{user_name}/releases`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-releases)
     pub fn stats_user_releases(
         &self,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
-        range: Option<&str>,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsUserReleasesResponse>, Error> {
-        self.get_stats(Endpoint::StatsUserReleases(user_name), count, offset, range)
+        self.get_paged(Endpoint::StatsUserReleases(user_name), count, offset, range)
+    }
+
+    /// Iterate over all statistics returned by [`Client::stats_user_releases`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn stats_user_releases_iter<'a>(
+        &'a self,
+        user_name: &'a UserName,
+        range: Option<StatsRange>,
+    ) -> OffsetIter<'a, StatsUserReleasesRelease> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.stats_user_releases(user_name, count, Some(offset), range.clone())?;
+            Ok(response.map(|response| Page {
+                items: response.payload.releases,
+                total: Some(response.payload.total_release_count),
+            }))
+        })
     }
 
     /// Endpoint: [`stats/user/{user_name}/artists`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-stats-user-(user_name)-artists)
     pub fn stats_user_artists(
         &self,
-        user_name: &str,
+        user_name: &UserName,
         count: Option<u64>,
         offset: Option<u64>,
-        range: Option<&str>,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsUserArtistsResponse>, Error> {
-        self.get_stats(Endpoint::StatsUserArtists(user_name), count, offset, range)
+        self.get_paged(Endpoint::StatsUserArtists(user_name), count, offset, range)
+    }
+
+    /// Iterate over all statistics returned by [`Client::stats_user_artists`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn stats_user_artists_iter<'a>(
+        &'a self,
+        user_name: &'a UserName,
+        range: Option<StatsRange>,
+    ) -> OffsetIter<'a, StatsUserArtistsArtist> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.stats_user_artists(user_name, count, Some(offset), range.clone())?;
+            Ok(response.map(|response| Page {
+                items: response.payload.artists,
+                total: Some(response.payload.total_artist_count),
+            }))
+        })
     }
 
     /// Endpoint: [`GET /1/stats/release-group/(release_group_mbid)/listeners`](https://listenbrainz.readthedocs.io/en/latest/users/api/statistics.html#get--1-stats-release-group-(release_group_mbid)-listeners)
     /// Get the top listeners for a release group, as well as getting the total number of listens for it
     pub fn stats_release_group_listeners(
         &self,
-        release_group_mbid: &str,
-        range: Option<&str>,
+        release_group_mbid: &Mbid,
+        range: Option<StatsRange>,
     ) -> Result<Option<StatsReleaseGroupListenersResponse>, Error> {
-        self.get_stats(
+        self.get_paged(
             Endpoint::StatsReleaseGroupListeners(release_group_mbid),
             None,
             None,
@@ -480,31 +875,340 @@ impl Client {
         )
     }
 
+    /// Endpoint: [`metadata/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-recording-)
+    ///
+    /// Get the metadata of one or more recordings, with the additional metadata in `inc`.
+    pub fn metadata_recording(
+        &self,
+        recording_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataRecordingResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataRecording)?
+            .param("recording_mbids", Mbid::join(recording_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`metadata/release_group`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-release_group-)
+    ///
+    /// Get the metadata of one or more release groups, with the additional metadata in `inc`.
+    pub fn metadata_release_group(
+        &self,
+        release_group_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataReleaseGroupResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataReleaseGroup)?
+            .param("release_group_mbids", Mbid::join(release_group_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`metadata/artist`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-artist-)
+    ///
+    /// Get the metadata of one or more artists, with the additional metadata in `inc`.
+    pub fn metadata_artist(
+        &self,
+        artist_mbids: &[Mbid],
+        inc: &[MetadataInclude],
+    ) -> Result<MetadataArtistResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataArtist)?
+            .param("artist_mbids", Mbid::join(artist_mbids))
+            .param("inc", MetadataInclude::join(inc));
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`metadata/lookup`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-lookup-)
+    ///
+    /// Look up a recording by the names of the artist, recording and (optionally) release.
+    /// If `metadata` is `Some`, the metadata of the recording is included as well,
+    /// with the given additional metadata.
+    pub fn metadata_lookup(
+        &self,
+        artist_name: &str,
+        recording_name: &str,
+        release_name: Option<&str>,
+        metadata: Option<&[MetadataInclude]>,
+    ) -> Result<MetadataLookupResponse, Error> {
+        let mut request = self
+            .request(Method::Get, Endpoint::MetadataLookup)?
+            .param("artist_name", artist_name)
+            .param("recording_name", recording_name);
+
+        if let Some(release_name) = release_name {
+            request = request.param("release_name", release_name);
+        }
+        if let Some(inc) = metadata {
+            request = request
+                .param("metadata", true)
+                .param("inc", MetadataInclude::join(inc));
+        }
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`metadata/submit_manual_mapping`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#post--1-metadata-submit_manual_mapping-)
+    ///
+    /// Link a listened recording (by MSID) to a MusicBrainz recording, for listens that
+    /// were not mapped automatically or were mapped to the wrong recording.
+    pub fn metadata_submit_manual_mapping<T: StrType>(
+        &self,
+        token: &str,
+        data: MetadataSubmitManualMapping<T>,
+    ) -> Result<MetadataSubmitManualMappingResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::MetadataSubmitManualMapping, token, data)
+    }
+
+    /// Endpoint: [`metadata/get_manual_mapping`](https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-get_manual_mapping-)
+    ///
+    /// Get the manual mapping that the user of the token submitted for a recording MSID.
+    pub fn metadata_get_manual_mapping(
+        &self,
+        token: &str,
+        recording_msid: &str,
+    ) -> Result<MetadataGetManualMappingResponse, Error> {
+        let request = self
+            .request(Method::Get, Endpoint::MetadataGetManualMapping)?
+            .token(token)
+            .param("recording_msid", recording_msid);
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Endpoint: [`popularity/top-recordings-for-artist/{artist_mbid}`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-recordings-for-artist-(artist_mbid))
+    ///
+    /// Get the most listened recordings of an artist.
+    pub fn popularity_top_recordings_for_artist(
+        &self,
+        artist_mbid: &Mbid,
+    ) -> Result<PopularityTopRecordingsForArtistResponse, Error> {
+        self.get(Endpoint::PopularityTopRecordingsForArtist(artist_mbid))
+    }
+
+    /// Endpoint: [`popularity/top-release-groups-for-artist/{artist_mbid}`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-release-groups-for-artist-(artist_mbid))
+    ///
+    /// Get the most listened release groups of an artist.
+    pub fn popularity_top_release_groups_for_artist(
+        &self,
+        artist_mbid: &Mbid,
+    ) -> Result<PopularityTopReleaseGroupsForArtistResponse, Error> {
+        self.get(Endpoint::PopularityTopReleaseGroupsForArtist(artist_mbid))
+    }
+
+    /// Endpoint: [`popularity/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-recording)
+    ///
+    /// Get the total number of listens and listeners of one or more recordings.
+    pub fn popularity_recording(
+        &self,
+        recording_mbids: &[Mbid],
+    ) -> Result<PopularityRecordingResponse, Error> {
+        self.post_mbids(
+            Endpoint::PopularityRecording,
+            "recording_mbids",
+            recording_mbids,
+        )
+    }
+
+    /// Endpoint: [`popularity/artist`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-artist)
+    ///
+    /// Get the total number of listens and listeners of one or more artists.
+    pub fn popularity_artist(
+        &self,
+        artist_mbids: &[Mbid],
+    ) -> Result<PopularityArtistResponse, Error> {
+        self.post_mbids(Endpoint::PopularityArtist, "artist_mbids", artist_mbids)
+    }
+
+    /// Endpoint: [`popularity/release`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release)
+    ///
+    /// Get the total number of listens and listeners of one or more releases.
+    pub fn popularity_release(
+        &self,
+        release_mbids: &[Mbid],
+    ) -> Result<PopularityReleaseResponse, Error> {
+        self.post_mbids(Endpoint::PopularityRelease, "release_mbids", release_mbids)
+    }
+
+    /// Endpoint: [`popularity/release-group`](https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release-group)
+    ///
+    /// Get the total number of listens and listeners of one or more release groups.
+    pub fn popularity_release_group(
+        &self,
+        release_group_mbids: &[Mbid],
+    ) -> Result<PopularityReleaseGroupResponse, Error> {
+        self.post_mbids(
+            Endpoint::PopularityReleaseGroup,
+            "release_group_mbids",
+            release_group_mbids,
+        )
+    }
+
+    /// Endpoint: [`cf/recommendation/user/{user_name}/recording`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-cf-recommendation-user-(user_name)-recording)
+    ///
+    /// Get the recordings recommended for a user by collaborative filtering.
+    /// Returns [`None`] if there are no recommendations for the user.
+    pub fn cf_recommendation_user_recording(
+        &self,
+        user_name: &UserName,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Option<CfRecommendationUserRecordingResponse>, Error> {
+        self.get_paged(
+            Endpoint::CfRecommendationUserRecording(user_name),
+            count,
+            offset,
+            None,
+        )
+    }
+
+    /// Iterate over all recommendations returned by [`Client::cf_recommendation_user_recording`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn cf_recommendation_user_recording_iter<'a>(
+        &'a self,
+        user_name: &'a UserName,
+    ) -> OffsetIter<'a, CfRecommendationUserRecordingMbid> {
+        OffsetIter::new(move |count, offset| {
+            let response = self.cf_recommendation_user_recording(user_name, count, Some(offset))?;
+            Ok(response.map(|response| Page {
+                items: response.payload.mbids,
+                total: Some(response.payload.total_mbid_count),
+            }))
+        })
+    }
+
+    /// Endpoint: [`recommendation/feedback/submit`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-submit)
+    ///
+    /// Submit the rating of the user of the token for a recommended recording,
+    /// replacing any earlier rating.
+    pub fn recommendation_feedback_submit<T: StrType>(
+        &self,
+        token: &str,
+        data: RecommendationFeedbackSubmit<T>,
+    ) -> Result<RecommendationFeedbackSubmitResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::RecommendationFeedbackSubmit, token, data)
+    }
+
+    /// Endpoint: [`recommendation/feedback/delete`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-delete)
+    ///
+    /// Delete the rating of the user of the token for a recommended recording.
+    pub fn recommendation_feedback_delete<T: StrType>(
+        &self,
+        token: &str,
+        data: RecommendationFeedbackDelete<T>,
+    ) -> Result<RecommendationFeedbackDeleteResponse, Error> {
+        Mbid::validate(data.recording_mbid.borrow())?;
+
+        self.post(Endpoint::RecommendationFeedbackDelete, token, data)
+    }
+
+    /// Endpoint: [`recommendation/feedback/user/{user_name}`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name))
+    ///
+    /// Get the ratings of a user for recommended recordings, newest first,
+    /// optionally only those with the given rating.
+    pub fn recommendation_feedback_user(
+        &self,
+        user_name: &UserName,
+        rating: Option<RecommendationFeedbackRating>,
+        count: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<RecommendationFeedbackUserResponse, Error> {
+        let mut request =
+            self.request(Method::Get, Endpoint::RecommendationFeedbackUser(user_name))?;
+
+        if let Some(rating) = rating {
+            request = request.param("rating", rating);
+        }
+        if let Some(count) = count {
+            request = request.param("count", count);
+        }
+        if let Some(offset) = offset {
+            request = request.param("offset", offset);
+        }
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
+    /// Iterate over all ratings returned by [`Client::recommendation_feedback_user`],
+    /// fetching pages as needed. See [`OffsetIter`] for details.
+    pub fn recommendation_feedback_user_iter<'a>(
+        &'a self,
+        user_name: &'a UserName,
+        rating: Option<RecommendationFeedbackRating>,
+    ) -> OffsetIter<'a, RecommendationFeedback> {
+        OffsetIter::new(move |count, offset| {
+            let response =
+                self.recommendation_feedback_user(user_name, rating, count, Some(offset))?;
+            Ok(Some(Page {
+                items: response.feedback,
+                total: Some(response.total_count),
+            }))
+        })
+    }
+
+    /// Endpoint: [`recommendation/feedback/user/{user_name}/recordings`](https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name)-recordings)
+    ///
+    /// Get the ratings of a user for the given recommended recordings.
+    pub fn recommendation_feedback_user_recordings(
+        &self,
+        user_name: &UserName,
+        recording_mbids: &[Mbid],
+    ) -> Result<RecommendationFeedbackUserRecordingsResponse, Error> {
+        let request = self
+            .request(
+                Method::Get,
+                Endpoint::RecommendationFeedbackUserRecordings(user_name),
+            )?
+            .param("mbids", Mbid::join(recording_mbids));
+
+        let response = self.send(request)?;
+
+        response.into_response()
+    }
+
     /// Endpoint: [`status/get-dump-info`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-status-get-dump-info)
     pub fn status_get_dump_info(
         &self,
         id: Option<i64>,
     ) -> Result<StatusGetDumpInfoResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::StatusGetDumpInfo);
-
-        let mut request = attohttpc::get(endpoint);
+        let mut request = self.request(Method::Get, Endpoint::StatusGetDumpInfo)?;
 
         if let Some(id) = id {
             request = request.param("id", id);
         }
 
-        let response = request.send()?;
+        let response = self.send(request)?;
 
-        ResponseType::from_response(response)
+        response.into_response()
     }
 
     /// Endpoint: [`user/{user_name}/followers`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-followers)
-    pub fn user_followers(&self, user_name: &str) -> Result<UserFollowersResponse, Error> {
+    pub fn user_followers(&self, user_name: &UserName) -> Result<UserFollowersResponse, Error> {
         self.get(Endpoint::UserFollowers(user_name))
     }
 
     /// Endpoint: [`user/{user_name}/following`](https://listenbrainz.readthedocs.io/en/production/dev/api/#get--1-user-(user_name)-following)
-    pub fn user_following(&self, user_name: &str) -> Result<UserFollowingResponse, Error> {
+    pub fn user_following(&self, user_name: &UserName) -> Result<UserFollowingResponse, Error> {
         self.get(Endpoint::UserFollowing(user_name))
     }
 
@@ -512,26 +1216,28 @@ impl Client {
     pub fn user_unfollow(
         &self,
         token: &str,
-        user_name: &str,
+        user_name: &UserName,
     ) -> Result<UserUnfollowResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::UserUnfollow(user_name));
+        let response = self.send(
+            self.request(Method::Post, Endpoint::UserUnfollow(user_name))?
+                .token(token),
+        )?;
 
-        let response = attohttpc::post(endpoint)
-            .header("Authorization", format!("Token {}", token))
-            .send()?;
-
-        ResponseType::from_response(response)
+        response.into_response()
     }
 
     /// Endpoint: [`user/{user_name}/follow`](https://listenbrainz.readthedocs.io/en/production/dev/api/#post--1-user-(user_name)-follow)
-    pub fn user_follow(&self, token: &str, user_name: &str) -> Result<UserFollowResponse, Error> {
-        let endpoint = format!("{}{}", self.api_root_url, Endpoint::UserFollow(user_name));
-
-        let response = attohttpc::post(endpoint)
-            .header("Authorization", format!("Token {}", token))
-            .send()?;
-
-        ResponseType::from_response(response)
+    pub fn user_follow(
+        &self,
+        token: &str,
+        user_name: &UserName,
+    ) -> Result<UserFollowResponse, Error> {
+        let response = self.send(
+            self.request(Method::Post, Endpoint::UserFollow(user_name))?
+                .token(token),
+        )?;
+
+        response.into_response()
     }
 }
 
@@ -540,3 +1246,775 @@ impl Default for Client {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::raw::ids::testing::{mbid, user};
+    use crate::raw::transport::mock::MockTransport;
+    use crate::raw::transport::stub::StubServer;
+
+    const LISTEN_COUNT: &str = r#"{"payload": {"count": 42}}"#;
+
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// A transport that fails with `status` the first `failures` times.
+    fn failing(status: u16, failures: u32) -> MockTransport {
+        let count = AtomicU32::new(0);
+        MockTransport::new(move |_| {
+            if count.fetch_add(1, Ordering::Relaxed) < failures {
+                Ok(HttpResponse::new(status, "Bad Gateway"))
+            } else {
+                Ok(HttpResponse::new(200, LISTEN_COUNT))
+            }
+        })
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let mut client = Client::new_with_transport(API_ROOT_URL, failing(502, 2));
+        client.set_retry_policy(retry_policy());
+
+        let response = client.user_listen_count(&user("koen")).unwrap();
+        assert_eq!(response.payload.count, 42);
+        assert_eq!(response.attempts, 3);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut client = Client::new_with_transport(API_ROOT_URL, failing(502, 5));
+        client.set_retry_policy(retry_policy());
+
+        let error = client.user_listen_count(&user("koen")).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Api {
+                code: 502,
+                attempts: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn does_not_retry_non_idempotent_requests_on_bad_gateway() {
+        let transport = failing(502, 1);
+        let mut client = Client::new_with_transport(API_ROOT_URL, transport.clone());
+        client.set_retry_policy(retry_policy());
+
+        let data = DeleteListen {
+            listened_at: 0,
+            recording_msid: "msid",
+        };
+        let error = client.delete_listen("token", data).unwrap_err();
+        assert!(matches!(error, Error::Api { attempts: 1, .. }));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn counts_attempts_per_request() {
+        let mut client = Client::new_with_transport(API_ROOT_URL, failing(502, 2));
+        client.set_retry_policy(retry_policy());
+        let client = Arc::new(client);
+
+        let response = client.user_listen_count(&user("koen")).unwrap();
+        assert_eq!(response.attempts, 3);
+
+        let other = Arc::clone(&client);
+        let response = thread::spawn(move || other.user_listen_count(&user("koen")).unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(response.attempts, 1);
+
+        let response = client.user_listen_count(&user("koen")).unwrap();
+        assert_eq!(response.attempts, 1);
+    }
+
+    #[test]
+    fn every_request_uses_the_configured_api_url() {
+        const ROOT: &str = "http://listenbrainz.example:8100/1/";
+        const MBID: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+        const USER: &str = "koen";
+        const TOKEN: &str = "token";
+
+        let transport =
+            MockTransport::new(|_| Ok(HttpResponse::new(404, r#"{"code": 404, "error": "x"}"#)));
+        let client = Client::new_with_transport(ROOT, transport.clone());
+        let playlist = PlaylistCreate {
+            playlist: PlaylistCreatePlaylist {
+                title: "title".to_string(),
+                annotation: None,
+                track: Vec::new(),
+                extension: PlaylistCreatePlaylistExtension {
+                    musicbrainz: PlaylistCreatePlaylistExtensionInner {
+                        created_for: None,
+                        creator: None,
+                        collaborators: Vec::new(),
+                        copied_from: None,
+                        copied_from_deleted: None,
+                        public: false,
+                        last_modified_at: None,
+                        additional_metadata: None,
+                    },
+                },
+            },
+        };
+        let playlist_edit = PlaylistEdit {
+            playlist: PlaylistEditPlaylist::from(&jspf::PlaylistInfo::default()),
+        };
+        let range = || Some(StatsRange::AllTime);
+
+        let mut calls = 0;
+        let mut call = |result: Result<(), Error>| {
+            calls += 1;
+            assert!(result.is_err());
+        };
+
+        let payload = [Payload {
+            listened_at: None,
+            track_metadata: TrackMetadata {
+                track_name: "track",
+                artist_name: "artist",
+                release_name: Some("release"),
+                additional_info: None,
+            },
+        }];
+        let listens = SubmitListens {
+            listen_type: ListenType::Single,
+            payload: &payload,
+        };
+        call(client.submit_listens(TOKEN, listens).map(drop));
+        call(client.validate_token(TOKEN).map(drop));
+        let delete = DeleteListen {
+            listened_at: 0,
+            recording_msid: MBID,
+        };
+        call(client.delete_listen(TOKEN, delete).map(drop));
+        call(
+            client
+                .user_playlists_collaborator(None, &user(USER), None, None)
+                .map(drop),
+        );
+        call(
+            client
+                .user_playlists_collaborator_iter(None, &user(USER))
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .user_playlists_created_for(None, &user(USER), None, None)
+                .map(drop),
+        );
+        call(
+            client
+                .user_playlists_created_for_iter(None, &user(USER))
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(client.user_similar_users(&user(USER)).map(drop));
+        call(client.user_listen_count(&user(USER)).map(drop));
+        call(client.user_playing_now(&user(USER)).map(drop));
+        call(
+            client
+                .user_similar_to(&user(USER), &user("other"))
+                .map(drop),
+        );
+        call(
+            client
+                .user_playlists(None, &user(USER), None, None)
+                .map(drop),
+        );
+        call(
+            client
+                .user_playlists_iter(None, &user(USER))
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(client.user_listens(&user(USER), None, None, None).map(drop));
+        call(
+            client
+                .user_listens_iter(&user(USER))
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(client.get_latest_import(&user(USER)).map(drop));
+        call(client.get_playlist(None, &mbid(MBID)).map(drop));
+        call(client.get_playlist_xspf(None, &mbid(MBID)).map(drop));
+        call(
+            client
+                .update_latest_import(TOKEN, UpdateLatestImport { ts: 0 })
+                .map(drop),
+        );
+        call(client.playlist_create(TOKEN, playlist).map(drop));
+        call(client.playlist_delete(TOKEN, &mbid(MBID)).map(drop));
+        call(client.playlist_copy(TOKEN, &mbid(MBID)).map(drop));
+        call(client.playlist_search("query", None, None).map(drop));
+        call(
+            client
+                .playlist_search_iter("query")
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .playlist_edit(TOKEN, &mbid(MBID), playlist_edit)
+                .map(drop),
+        );
+        let item_add = PlaylistItemAdd {
+            playlist: PlaylistItemAddPlaylist { track: Vec::new() },
+        };
+        call(
+            client
+                .playlist_item_add(TOKEN, &mbid(MBID), Some(1), item_add)
+                .map(drop),
+        );
+        let item_move = PlaylistItemMove {
+            mbid: MBID.to_string(),
+            from: 0,
+            to: 1,
+            count: 1,
+        };
+        call(
+            client
+                .playlist_item_move(TOKEN, &mbid(MBID), item_move)
+                .map(drop),
+        );
+        let item_delete = PlaylistItemDelete { index: 0, count: 1 };
+        call(
+            client
+                .playlist_item_delete(TOKEN, &mbid(MBID), item_delete)
+                .map(drop),
+        );
+        call(
+            client
+                .playlist_export(TOKEN, &mbid(MBID), MusicService::Spotify)
+                .map(drop),
+        );
+        let jspf = jspf::Playlist::default();
+        call(
+            client
+                .playlist_export_jspf(TOKEN, MusicService::Spotify, &jspf)
+                .map(drop),
+        );
+        call(
+            client
+                .playlist_import(TOKEN, MusicService::Spotify)
+                .map(drop),
+        );
+        call(
+            client
+                .playlist_service_tracks(TOKEN, MusicService::Spotify, "id")
+                .map(drop),
+        );
+        call(client.stats_sitewide_artists(None, None, range()).map(drop));
+        call(
+            client
+                .stats_sitewide_artists_iter(range())
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_listening_activity(&user(USER), range())
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_daily_activity(&user(USER), range())
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_recordings(&user(USER), None, None, range())
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_recordings_iter(&user(USER), range())
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_artist_map(&user(USER), range(), None)
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_releases(&user(USER), None, None, range())
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_releases_iter(&user(USER), range())
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_artists(&user(USER), None, None, range())
+                .map(drop),
+        );
+        call(
+            client
+                .stats_user_artists_iter(&user(USER), range())
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .stats_release_group_listeners(&mbid(MBID), range())
+                .map(drop),
+        );
+        call(client.metadata_recording(&[mbid(MBID)], &[]).map(drop));
+        call(client.metadata_release_group(&[mbid(MBID)], &[]).map(drop));
+        call(client.metadata_artist(&[mbid(MBID)], &[]).map(drop));
+        call(
+            client
+                .metadata_lookup("artist", "recording", None, None)
+                .map(drop),
+        );
+        let mapping = MetadataSubmitManualMapping {
+            recording_msid: MBID,
+            recording_mbid: MBID,
+        };
+        call(
+            client
+                .metadata_submit_manual_mapping(TOKEN, mapping)
+                .map(drop),
+        );
+        call(client.metadata_get_manual_mapping(TOKEN, MBID).map(drop));
+        call(
+            client
+                .popularity_top_recordings_for_artist(&mbid(MBID))
+                .map(drop),
+        );
+        call(
+            client
+                .popularity_top_release_groups_for_artist(&mbid(MBID))
+                .map(drop),
+        );
+        call(client.popularity_recording(&[mbid(MBID)]).map(drop));
+        call(client.popularity_artist(&[mbid(MBID)]).map(drop));
+        call(client.popularity_release(&[mbid(MBID)]).map(drop));
+        call(client.popularity_release_group(&[mbid(MBID)]).map(drop));
+        call(
+            client
+                .cf_recommendation_user_recording(&user(USER), None, None)
+                .map(drop),
+        );
+        call(
+            client
+                .cf_recommendation_user_recording_iter(&user(USER))
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        let feedback = RecommendationFeedbackSubmit {
+            recording_mbid: MBID,
+            rating: RecommendationFeedbackRating::Love,
+        };
+        call(
+            client
+                .recommendation_feedback_submit(TOKEN, feedback)
+                .map(drop),
+        );
+        let feedback = RecommendationFeedbackDelete {
+            recording_mbid: MBID,
+        };
+        call(
+            client
+                .recommendation_feedback_delete(TOKEN, feedback)
+                .map(drop),
+        );
+        call(
+            client
+                .recommendation_feedback_user(&user(USER), None, None, None)
+                .map(drop),
+        );
+        call(
+            client
+                .recommendation_feedback_user_iter(&user(USER), None)
+                .next()
+                .unwrap()
+                .map(drop),
+        );
+        call(
+            client
+                .recommendation_feedback_user_recordings(&user(USER), &[mbid(MBID)])
+                .map(drop),
+        );
+        call(client.status_get_dump_info(None).map(drop));
+        call(client.user_followers(&user(USER)).map(drop));
+        call(client.user_following(&user(USER)).map(drop));
+        call(client.user_unfollow(TOKEN, &user(USER)).map(drop));
+        call(client.user_follow(TOKEN, &user(USER)).map(drop));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), calls);
+        for request in &requests {
+            assert!(
+                request.url.starts_with(ROOT),
+                "{} ignores the API URL",
+                request.url
+            );
+        }
+
+        let urls: Vec<_> = requests
+            .iter()
+            .map(|request| request.url.as_str())
+            .collect();
+        for url in [
+            "http://listenbrainz.example:8100/1/stats/user/koen/listening-activity",
+            "http://listenbrainz.example:8100/1/stats/user/koen/daily-activity",
+            "http://listenbrainz.example:8100/1/stats/user/koen/artist-map",
+        ] {
+            assert!(urls.contains(&url), "no request to {url}");
+        }
+    }
+
+    #[test]
+    fn sends_requests_over_http_to_the_configured_api_url() {
+        let server = StubServer::start(200, LISTEN_COUNT);
+
+        let client = Client::new_with_url(server.url());
+        let response = client.user_listen_count(&user("koen")).unwrap();
+        assert_eq!(response.payload.count, 42);
+        assert_eq!(response.rate_limit.unwrap().remaining, 29);
+
+        let client = Client::builder()
+            .api_url(server.url())
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        client.user_playing_now(&user("koen")).unwrap_err();
+
+        assert_eq!(
+            server.requests(),
+            [
+                "GET /1/user/koen/listen-count",
+                "GET /1/user/koen/playing-now",
+            ]
+        );
+    }
+
+    #[test]
+    fn posts_popularity_mbids() {
+        const A: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+        const B: &str = "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3";
+
+        let transport = MockTransport::new(|_| Ok(HttpResponse::new(200, "[]")));
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+        let both = [mbid(A), mbid(B)];
+
+        client.popularity_recording(&both).unwrap();
+        client.popularity_artist(&[mbid(A)]).unwrap();
+        client.popularity_release(&both).unwrap();
+        let response = client.popularity_release_group(&[]).unwrap();
+        assert!(response.release_groups.is_empty());
+
+        let requests: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .map(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(&request.body.unwrap()).unwrap();
+                (request.method, request.url, body)
+            })
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                (
+                    Method::Post,
+                    "http://localhost/1/popularity/recording".to_string(),
+                    serde_json::json!({ "recording_mbids": [A, B] }),
+                ),
+                (
+                    Method::Post,
+                    "http://localhost/1/popularity/artist".to_string(),
+                    serde_json::json!({ "artist_mbids": [A] }),
+                ),
+                (
+                    Method::Post,
+                    "http://localhost/1/popularity/release".to_string(),
+                    serde_json::json!({ "release_mbids": [A, B] }),
+                ),
+                (
+                    Method::Post,
+                    "http://localhost/1/popularity/release-group".to_string(),
+                    serde_json::json!({ "release_group_mbids": [] }),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn posts_recommendation_feedback() {
+        const MBID: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+
+        let transport = MockTransport::new(|_| Ok(HttpResponse::new(200, r#"{"status": "ok"}"#)));
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        let feedback = RecommendationFeedbackSubmit {
+            recording_mbid: MBID,
+            rating: RecommendationFeedbackRating::BadRecommendation,
+        };
+        let response = client
+            .recommendation_feedback_submit("token", feedback)
+            .unwrap();
+        assert_eq!(response.status, "ok");
+        let feedback = RecommendationFeedbackDelete {
+            recording_mbid: MBID.to_string(),
+        };
+        client
+            .recommendation_feedback_delete("token", feedback)
+            .unwrap();
+
+        let requests: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .map(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(&request.body.unwrap()).unwrap();
+                (request.method, request.url, body)
+            })
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                (
+                    Method::Post,
+                    "http://localhost/1/recommendation/feedback/submit".to_string(),
+                    serde_json::json!({
+                        "recording_mbid": MBID,
+                        "rating": "bad_recommendation",
+                    }),
+                ),
+                (
+                    Method::Post,
+                    "http://localhost/1/recommendation/feedback/delete".to_string(),
+                    serde_json::json!({ "recording_mbid": MBID }),
+                ),
+            ]
+        );
+
+        let feedback = RecommendationFeedbackSubmit {
+            recording_mbid: "not an MBID",
+            rating: RecommendationFeedbackRating::Love,
+        };
+        assert!(matches!(
+            client.recommendation_feedback_submit("token", feedback),
+            Err(Error::InvalidMbid(_))
+        ));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[test]
+    fn serializes_recommendation_feedback_ratings() {
+        let ratings = [
+            RecommendationFeedbackRating::Love,
+            RecommendationFeedbackRating::Like,
+            RecommendationFeedbackRating::Dislike,
+            RecommendationFeedbackRating::Hate,
+            RecommendationFeedbackRating::BadRecommendation,
+        ];
+        let names = ["love", "like", "dislike", "hate", "bad_recommendation"];
+
+        assert_eq!(
+            serde_json::to_value(ratings).unwrap(),
+            serde_json::json!(names)
+        );
+        for (rating, name) in ratings.into_iter().zip(names) {
+            assert_eq!(rating.as_str(), name);
+            assert_eq!(rating.to_string(), name);
+            assert_eq!(
+                serde_json::from_value::<RecommendationFeedbackRating>(name.into()).unwrap(),
+                rating
+            );
+        }
+    }
+
+    /// Get the query parameters of all requests sent so far.
+    fn params(transport: &MockTransport) -> Vec<Vec<(String, String)>> {
+        transport
+            .requests()
+            .into_iter()
+            .map(|request| request.params)
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn builds_metadata_queries() {
+        const A: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+        const B: &str = "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3";
+
+        let transport = MockTransport::new(|request| {
+            let body = if request.url.ends_with("/artist/") {
+                "[]"
+            } else {
+                "{}"
+            };
+            Ok(HttpResponse::new(200, body))
+        });
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+        let all = [
+            MetadataInclude::Artist,
+            MetadataInclude::Tag,
+            MetadataInclude::Release,
+        ];
+
+        let response = client
+            .metadata_recording(&[mbid(A), mbid(B)], &all)
+            .unwrap();
+        assert!(response.recordings.is_empty());
+        client
+            .metadata_release_group(&[mbid(A)], &[MetadataInclude::Tag])
+            .unwrap();
+        client.metadata_artist(&[mbid(A), mbid(B)], &[]).unwrap();
+
+        let urls: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .map(|request| (request.method, request.url))
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                (
+                    Method::Get,
+                    "http://localhost/1/metadata/recording/".to_string()
+                ),
+                (
+                    Method::Get,
+                    "http://localhost/1/metadata/release_group/".to_string()
+                ),
+                (
+                    Method::Get,
+                    "http://localhost/1/metadata/artist/".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            params(&transport),
+            [
+                pairs(&[
+                    ("recording_mbids", &format!("{A},{B}")),
+                    ("inc", "artist tag release"),
+                ]),
+                pairs(&[("release_group_mbids", A), ("inc", "tag")]),
+                pairs(&[("artist_mbids", &format!("{A},{B}")), ("inc", "")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_metadata_lookups() {
+        let transport = MockTransport::new(|_| Ok(HttpResponse::new(200, "{}")));
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        let response = client
+            .metadata_lookup("Portishead", "Strangers", None, None)
+            .unwrap();
+        assert_eq!(response.recording_mbid, None);
+        client
+            .metadata_lookup(
+                "Portishead",
+                "Strangers",
+                Some("Dummy"),
+                Some(&[MetadataInclude::Release, MetadataInclude::Tag]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            transport.requests()[0].url,
+            "http://localhost/1/metadata/lookup/"
+        );
+        assert_eq!(
+            params(&transport),
+            [
+                pairs(&[
+                    ("artist_name", "Portishead"),
+                    ("recording_name", "Strangers")
+                ]),
+                pairs(&[
+                    ("artist_name", "Portishead"),
+                    ("recording_name", "Strangers"),
+                    ("release_name", "Dummy"),
+                    ("metadata", "true"),
+                    ("inc", "release tag"),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn serializes_stats_ranges() {
+        let ranges = [
+            (StatsRange::ThisWeek, "this_week"),
+            (StatsRange::ThisMonth, "this_month"),
+            (StatsRange::ThisYear, "this_year"),
+            (StatsRange::Week, "week"),
+            (StatsRange::Month, "month"),
+            (StatsRange::Quarter, "quarter"),
+            (StatsRange::Year, "year"),
+            (StatsRange::HalfYearly, "half_yearly"),
+            (StatsRange::AllTime, "all_time"),
+            (StatsRange::Other("decade".to_string()), "decade"),
+        ];
+
+        for (range, name) in ranges {
+            assert_eq!(serde_json::to_value(&range).unwrap(), name);
+            assert_eq!(
+                serde_json::from_value::<StatsRange>(name.into()).unwrap(),
+                range
+            );
+            assert_eq!(range.to_string(), name);
+            assert_eq!(name.parse::<StatsRange>().unwrap(), range);
+            assert_eq!(StatsRange::from(name.to_string()), range);
+        }
+    }
+
+    #[test]
+    fn serializes_metadata_includes() {
+        assert_eq!(
+            serde_json::to_value([
+                MetadataInclude::Artist,
+                MetadataInclude::Tag,
+                MetadataInclude::Release,
+            ])
+            .unwrap(),
+            serde_json::json!(["artist", "tag", "release"])
+        );
+        assert_eq!(MetadataInclude::Release.to_string(), "release");
+        assert_eq!(MetadataInclude::join(&[]), "");
+    }
+}
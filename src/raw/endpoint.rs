@@ -1,35 +1,113 @@
 use std::fmt;
 
+use percent_encoding::{utf8_percent_encode, AsciiSet, PercentEncode, CONTROLS};
+
+use super::ids::{is_dot_segment, Mbid, UserName};
+use super::request::MusicService;
+use crate::Error;
+
+/// Characters that are percent-encoded in a path segment: everything that is not
+/// allowed in a segment according to the URL standard, and the segment separators `/` and `\`
+/// (which URL parsers treat like `/` in `http` URLs).
+/// `%` is included as well, so that segments are never decoded into something else.
+///
+/// The dot segments `.` and `..` cannot be escaped this way, since `%2E` is normalised
+/// like `.`; they are rejected by [`UserName::new`] and [`Endpoint::validate`] instead.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Percent-encode a dynamic path segment.
+fn segment(value: &str) -> PercentEncode<'_> {
+    utf8_percent_encode(value, SEGMENT)
+}
+
 pub enum Endpoint<'a> {
     SubmitListens,
     ValidateToken,
     DeleteListen,
-    UserPlaylistsCollaborator(&'a str),
-    UserPlaylistsCreatedFor(&'a str),
-    UserSimilarUsers(&'a str),
-    UserListenCount(&'a str),
-    UserPlayingNow(&'a str),
-    UserSimilarTo(&'a str, &'a str),
-    UserPlaylists(&'a str),
-    UserListens(&'a str),
+    UserPlaylistsCollaborator(&'a UserName),
+    UserPlaylistsCreatedFor(&'a UserName),
+    UserSimilarUsers(&'a UserName),
+    UserListenCount(&'a UserName),
+    UserPlayingNow(&'a UserName),
+    UserSimilarTo(&'a UserName, &'a UserName),
+    UserPlaylists(&'a UserName),
+    UserListens(&'a UserName),
     LatestImport,
-    Playlist(&'a str),
+    Playlist(&'a Mbid),
+    PlaylistXspf(&'a Mbid),
     PlaylistCreate,
-    PlaylistDelete(&'a str),
-    PlaylistCopy(&'a str),
+    PlaylistSearch,
+    PlaylistDelete(&'a Mbid),
+    PlaylistCopy(&'a Mbid),
+    PlaylistEdit(&'a Mbid),
+    PlaylistItemAdd(&'a Mbid, Option<u64>),
+    PlaylistItemMove(&'a Mbid),
+    PlaylistItemDelete(&'a Mbid),
+    PlaylistExport(&'a Mbid, MusicService),
+    PlaylistExportJspf(MusicService),
+    PlaylistImport(MusicService),
+    PlaylistServiceTracks(MusicService, &'a str),
     StatsSitewideArtists,
-    StatsUserListeningActivity(&'a str),
-    StatsUserDailyActivity(&'a str),
-    StatsUserRecordings(&'a str),
-    StatsUserArtistMap(&'a str),
-    StatsUserReleases(&'a str),
-    StatsUserArtists(&'a str),
-    StatsReleaseGroupListeners(&'a str),
+    StatsUserListeningActivity(&'a UserName),
+    StatsUserDailyActivity(&'a UserName),
+    StatsUserRecordings(&'a UserName),
+    StatsUserArtistMap(&'a UserName),
+    StatsUserReleases(&'a UserName),
+    StatsUserArtists(&'a UserName),
+    StatsReleaseGroupListeners(&'a Mbid),
     StatusGetDumpInfo,
-    UserFollowers(&'a str),
-    UserFollowing(&'a str),
-    UserUnfollow(&'a str),
-    UserFollow(&'a str),
+    MetadataRecording,
+    MetadataReleaseGroup,
+    MetadataArtist,
+    MetadataLookup,
+    MetadataSubmitManualMapping,
+    MetadataGetManualMapping,
+    PopularityTopRecordingsForArtist(&'a Mbid),
+    PopularityTopReleaseGroupsForArtist(&'a Mbid),
+    PopularityRecording,
+    PopularityArtist,
+    PopularityRelease,
+    PopularityReleaseGroup,
+    CfRecommendationUserRecording(&'a UserName),
+    RecommendationFeedbackSubmit,
+    RecommendationFeedbackDelete,
+    RecommendationFeedbackUser(&'a UserName),
+    RecommendationFeedbackUserRecordings(&'a UserName),
+    UserFollowers(&'a UserName),
+    UserFollowing(&'a UserName),
+    UserUnfollow(&'a UserName),
+    UserFollow(&'a UserName),
+}
+
+impl Endpoint<'_> {
+    /// Check that the dynamic segments in the path of this endpoint are valid.
+    ///
+    /// User names and MBIDs are validated when they are constructed, so this only checks
+    /// the IDs of playlists of music services.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self {
+            Self::PlaylistServiceTracks(_, playlist_id) => {
+                if playlist_id.is_empty() || is_dot_segment(playlist_id) {
+                    let message = format!("invalid playlist ID: {playlist_id:?}");
+                    return Err(Error::InvalidPlaylist(message));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<'a> fmt::Display for Endpoint<'a> {
@@ -39,41 +117,179 @@ impl<'a> fmt::Display for Endpoint<'a> {
             Self::ValidateToken => write!(f, "validate-token"),
             Self::DeleteListen => write!(f, "delete-listen"),
             Self::UserPlaylistsCollaborator(user) => {
-                write!(f, "user/{}/playlists/collaborator", user)
+                write!(f, "user/{}/playlists/collaborator", segment(user))
+            }
+            Self::UserPlaylistsCreatedFor(user) => {
+                write!(f, "user/{}/playlists/createdfor", segment(user))
             }
-            Self::UserPlaylistsCreatedFor(user) => write!(f, "user/{}/playlists/createdfor", user),
-            Self::UserSimilarUsers(user) => write!(f, "user/{}/similar-users", user),
-            Self::UserListenCount(user) => write!(f, "user/{}/listen-count", user),
-            Self::UserPlayingNow(user) => write!(f, "user/{}/playing-now", user),
+            Self::UserSimilarUsers(user) => write!(f, "user/{}/similar-users", segment(user)),
+            Self::UserListenCount(user) => write!(f, "user/{}/listen-count", segment(user)),
+            Self::UserPlayingNow(user) => write!(f, "user/{}/playing-now", segment(user)),
             Self::UserSimilarTo(user, other_user) => {
-                write!(f, "user/{}/similar-to/{}", user, other_user)
+                write!(
+                    f,
+                    "user/{}/similar-to/{}",
+                    segment(user),
+                    segment(other_user)
+                )
             }
-            Self::UserPlaylists(user) => write!(f, "user/{}/playlists", user),
-            Self::UserListens(user) => write!(f, "user/{}/listens", user),
+            Self::UserPlaylists(user) => write!(f, "user/{}/playlists", segment(user)),
+            Self::UserListens(user) => write!(f, "user/{}/listens", segment(user)),
             Self::LatestImport => write!(f, "latest-import"),
-            Self::Playlist(playlist) => write!(f, "playlist/{}", playlist),
+            Self::Playlist(playlist) => write!(f, "playlist/{}", segment(playlist)),
+            Self::PlaylistXspf(playlist) => write!(f, "playlist/{}/xspf", segment(playlist)),
             Self::PlaylistCreate => write!(f, "playlist/create"),
-            Self::PlaylistDelete(playlist) => write!(f, "playlist/{}/delete", playlist),
-            Self::PlaylistCopy(playlist) => write!(f, "playlist/{}/copy", playlist),
+            Self::PlaylistSearch => write!(f, "playlist/search"),
+            Self::PlaylistDelete(playlist) => write!(f, "playlist/{}/delete", segment(playlist)),
+            Self::PlaylistCopy(playlist) => write!(f, "playlist/{}/copy", segment(playlist)),
+            Self::PlaylistEdit(playlist) => write!(f, "playlist/edit/{}", segment(playlist)),
+            Self::PlaylistItemAdd(playlist, None) => {
+                write!(f, "playlist/{}/item/add", segment(playlist))
+            }
+            Self::PlaylistItemAdd(playlist, Some(offset)) => {
+                write!(f, "playlist/{}/item/add/{}", segment(playlist), offset)
+            }
+            Self::PlaylistItemMove(playlist) => {
+                write!(f, "playlist/{}/item/move", segment(playlist))
+            }
+            Self::PlaylistItemDelete(playlist) => {
+                write!(f, "playlist/{}/item/delete", segment(playlist))
+            }
+            Self::PlaylistExport(playlist, service) => {
+                write!(f, "playlist/{}/export/{}", segment(playlist), service)
+            }
+            Self::PlaylistExportJspf(service) => write!(f, "playlist/export-jspf/{}", service),
+            Self::PlaylistImport(service) => write!(f, "playlist/import/{}", service),
+            Self::PlaylistServiceTracks(service, playlist_id) => {
+                write!(f, "playlist/{}/{}/tracks", service, segment(playlist_id))
+            }
             Self::StatsSitewideArtists => write!(f, "stats/sitewide/artists"),
             Self::StatsUserListeningActivity(user) => {
-                write!(f, "stats/user/{}/listening-activity", user)
+                write!(f, "stats/user/{}/listening-activity", segment(user))
             }
             Self::StatsUserDailyActivity(user) => {
-                write!(f, "stats/user/{}/daily-activity", user)
+                write!(f, "stats/user/{}/daily-activity", segment(user))
             }
-            Self::StatsUserRecordings(user) => write!(f, "stats/user/{}/recordings", user),
-            Self::StatsUserArtistMap(user) => write!(f, "stats/user/{}/artist-map", user),
-            Self::StatsUserReleases(user) => write!(f, "stats/user/{}/releases", user),
-            Self::StatsUserArtists(user) => write!(f, "stats/user/{}/artists", user),
+            Self::StatsUserRecordings(user) => write!(f, "stats/user/{}/recordings", segment(user)),
+            Self::StatsUserArtistMap(user) => write!(f, "stats/user/{}/artist-map", segment(user)),
+            Self::StatsUserReleases(user) => write!(f, "stats/user/{}/releases", segment(user)),
+            Self::StatsUserArtists(user) => write!(f, "stats/user/{}/artists", segment(user)),
             Self::StatsReleaseGroupListeners(release_group_mbid) => {
-                return write!(f, "stats/release-group/{release_group_mbid}/listeners")
+                write!(
+                    f,
+                    "stats/release-group/{}/listeners",
+                    segment(release_group_mbid)
+                )
             }
             Self::StatusGetDumpInfo => write!(f, "status/get-dump-info"),
-            Self::UserFollowers(user) => write!(f, "user/{}/followers", user),
-            Self::UserFollowing(user) => write!(f, "user/{}/following", user),
-            Self::UserUnfollow(user) => write!(f, "user/{}/unfollow", user),
-            Self::UserFollow(user) => write!(f, "user/{}/follow", user),
+            Self::MetadataRecording => write!(f, "metadata/recording/"),
+            Self::MetadataReleaseGroup => write!(f, "metadata/release_group/"),
+            Self::MetadataArtist => write!(f, "metadata/artist/"),
+            Self::MetadataLookup => write!(f, "metadata/lookup/"),
+            Self::MetadataSubmitManualMapping => write!(f, "metadata/submit_manual_mapping/"),
+            Self::MetadataGetManualMapping => write!(f, "metadata/get_manual_mapping/"),
+            Self::PopularityTopRecordingsForArtist(artist_mbid) => {
+                write!(
+                    f,
+                    "popularity/top-recordings-for-artist/{}",
+                    segment(artist_mbid)
+                )
+            }
+            Self::PopularityTopReleaseGroupsForArtist(artist_mbid) => {
+                write!(
+                    f,
+                    "popularity/top-release-groups-for-artist/{}",
+                    segment(artist_mbid)
+                )
+            }
+            Self::PopularityRecording => write!(f, "popularity/recording"),
+            Self::PopularityArtist => write!(f, "popularity/artist"),
+            Self::PopularityRelease => write!(f, "popularity/release"),
+            Self::PopularityReleaseGroup => write!(f, "popularity/release-group"),
+            Self::CfRecommendationUserRecording(user) => {
+                write!(f, "cf/recommendation/user/{}/recording", segment(user))
+            }
+            Self::RecommendationFeedbackSubmit => write!(f, "recommendation/feedback/submit"),
+            Self::RecommendationFeedbackDelete => write!(f, "recommendation/feedback/delete"),
+            Self::RecommendationFeedbackUser(user) => {
+                write!(f, "recommendation/feedback/user/{}", segment(user))
+            }
+            Self::RecommendationFeedbackUserRecordings(user) => {
+                write!(
+                    f,
+                    "recommendation/feedback/user/{}/recordings",
+                    segment(user)
+                )
+            }
+            Self::UserFollowers(user) => write!(f, "user/{}/followers", segment(user)),
+            Self::UserFollowing(user) => write!(f, "user/{}/following", segment(user)),
+            Self::UserUnfollow(user) => write!(f, "user/{}/unfollow", segment(user)),
+            Self::UserFollow(user) => write!(f, "user/{}/follow", segment(user)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    /// Build the URL of `endpoint`, and parse it the way the HTTP client will.
+    fn url(endpoint: Endpoint) -> Result<Url, Error> {
+        endpoint.validate()?;
+        Ok(Url::parse(&format!("https://api.listenbrainz.org/1/{endpoint}")).unwrap())
+    }
+
+    #[test]
+    fn encodes_user_names() {
+        for (user, encoded) in [
+            ("koen", "koen"),
+            ("a/b", "a%2Fb"),
+            ("a?b", "a%3Fb"),
+            ("a#b", "a%23b"),
+            ("a b", "a%20b"),
+            ("a\\b", "a%5Cb"),
+            ("a%2Fb", "a%252Fb"),
+            ("%2e%2e", "%252e%252e"),
+            ("...", "..."),
+        ] {
+            let user = UserName::new(user).unwrap();
+            let url = url(Endpoint::UserListens(&user)).unwrap();
+            assert_eq!(url.path(), format!("/1/user/{encoded}/listens"));
+            assert_eq!(url.query(), None);
+            assert_eq!(url.fragment(), None);
+        }
+    }
+
+    #[test]
+    fn encodes_all_user_segments() {
+        let user = UserName::new("a/b").unwrap();
+        let other_user = UserName::new("c?d").unwrap();
+        let url = url(Endpoint::UserSimilarTo(&user, &other_user)).unwrap();
+        assert_eq!(url.path(), "/1/user/a%2Fb/similar-to/c%3Fd");
+    }
+
+    #[test]
+    fn rejects_dot_segments() {
+        for playlist_id in ["", ".", ".."] {
+            assert!(matches!(
+                url(Endpoint::PlaylistServiceTracks(
+                    MusicService::Spotify,
+                    playlist_id
+                )),
+                Err(Error::InvalidPlaylist(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn encodes_service_playlist_ids() {
+        let url = url(Endpoint::PlaylistServiceTracks(
+            MusicService::Spotify,
+            "a/b",
+        ))
+        .unwrap();
+        assert_eq!(url.path(), "/1/playlist/spotify/a%2Fb/tracks");
+    }
+}
@@ -0,0 +1,209 @@
+//! Validated identifiers used in the paths of API endpoints.
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// A ListenBrainz (MusicBrainz) user name.
+///
+/// A user name must not be empty, must not contain control characters,
+/// and must not be `.` or `..`.
+/// [`Client`](super::Client) methods take a `&UserName`, so invalid user names are
+/// rejected when the `UserName` is constructed, before any request is sent:
+///
+/// ```no_run
+/// # use listenbrainz::raw::{Client, UserName};
+/// let user = UserName::new("koen")?;
+/// let count = Client::new().user_listen_count(&user)?;
+/// # Ok::<(), listenbrainz::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UserName(String);
+
+impl UserName {
+    /// Construct a user name, returning [`Error::InvalidUserName`] if it is not valid.
+    pub fn new(user_name: impl Into<String>) -> Result<Self, Error> {
+        let user_name = user_name.into();
+        Self::validate(&user_name)?;
+        Ok(Self(user_name))
+    }
+
+    /// Get the user name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if `user_name` is a valid user name.
+    pub(crate) fn validate(user_name: &str) -> Result<(), Error> {
+        if user_name.trim().is_empty()
+            || user_name.chars().any(char::is_control)
+            || is_dot_segment(user_name)
+        {
+            return Err(Error::InvalidUserName(user_name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// A MusicBrainz identifier (MBID), such as the MBID of a playlist or release group.
+///
+/// An MBID is a UUID in its hyphenated form, e.g. `8f3471b5-7e6a-48da-86a9-c1c07a0f47ae`.
+/// Like with [`UserName`], [`Client`](super::Client) methods take a `&Mbid` (or `&[Mbid]`),
+/// so invalid MBIDs are rejected when the `Mbid` is constructed.
+///
+/// `UserName` and `Mbid` dereference to `str`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mbid(String);
+
+impl Mbid {
+    /// Construct an MBID, returning [`Error::InvalidMbid`] if it is not valid.
+    pub fn new(mbid: impl Into<String>) -> Result<Self, Error> {
+        let mbid = mbid.into();
+        Self::validate(&mbid)?;
+        Ok(Self(mbid))
+    }
+
+    /// Get the MBID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if `mbid` is a valid MBID.
+    pub(crate) fn validate(mbid: &str) -> Result<(), Error> {
+        let valid = mbid.len() == 36
+            && mbid.char_indices().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidMbid(mbid.to_string()))
+        }
+    }
+
+    /// Join `mbids` into a comma separated list, as used by the query parameters
+    /// of batch lookups.
+    pub(crate) fn join(mbids: &[Mbid]) -> String {
+        let mbids: Vec<&str> = mbids.iter().map(Mbid::as_str).collect();
+        mbids.join(",")
+    }
+}
+
+/// Check if `value` is a path segment that URL parsers resolve against the rest of the path.
+pub(super) fn is_dot_segment(value: &str) -> bool {
+    value == "." || value == ".."
+}
+
+macro_rules! impl_str_newtype {
+    ($name:ident) => {
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Error> {
+                Self::new(s)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = Error;
+
+            fn try_from(value: &str) -> Result<Self, Error> {
+                Self::new(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<Self, Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.0
+            }
+        }
+    };
+}
+
+impl_str_newtype!(UserName);
+impl_str_newtype!(Mbid);
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::{Mbid, UserName};
+
+    /// Construct a user name that is known to be valid.
+    pub(crate) fn user(user_name: &str) -> UserName {
+        UserName::new(user_name).unwrap()
+    }
+
+    /// Construct an MBID that is known to be valid.
+    pub(crate) fn mbid(mbid: &str) -> Mbid {
+        Mbid::new(mbid).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_names() {
+        for valid in [
+            "koen", "a/b", "a?b", "a#b", "a b", "a\\b", "...", ".koen", "%2e%2e",
+        ] {
+            assert!(UserName::new(valid).is_ok(), "{valid:?} should be valid");
+        }
+        for invalid in ["", " ", ".", "..", "a\nb", "a\0b"] {
+            assert!(
+                matches!(UserName::new(invalid), Err(Error::InvalidUserName(_))),
+                "{invalid:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn mbids() {
+        assert!(Mbid::new("8f3471b5-7e6a-48da-86a9-c1c07a0f47ae").is_ok());
+        for invalid in [
+            "",
+            "..",
+            "8f3471b5-7e6a-48da-86a9-c1c07a0f47a",
+            "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae0",
+            "8f3471b57e6a-48da-86a9-c1c07a0f47ae0",
+            "8f3471b5-7e6a-48da-86a9-c1c07a0f47ag",
+        ] {
+            assert!(
+                matches!(Mbid::new(invalid), Err(Error::InvalidMbid(_))),
+                "{invalid:?} should be invalid"
+            );
+        }
+    }
+}
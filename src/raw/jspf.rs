@@ -1,87 +1,514 @@
 //! Types dealing with playlists in the [JSPF format], specifically, [MusicBrainz's format].
 //!
+//! All fields of the JSPF format are modelled, and all of them are optional, so that
+//! playlists that were not created by ListenBrainz can be read as well.
+//! Extensions of other applications, and fields of the MusicBrainz extensions that are not
+//! known to this library, are kept in the `other` maps, so that a playlist is serialized
+//! without losing anything.
+//!
 //! [JSPF format]: https://xspf.org/jspf/
 //! [MusicBrainz's format]: https://musicbrainz.org/doc/jspf
 
 #![allow(missing_docs)]
 
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::Mbid;
 
 /// Top-level playlist type.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct Playlist {
     pub playlist: PlaylistInfo,
 }
 
 /// Type of the [`Playlist::playlist`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct PlaylistInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub annotation: Option<String>,
+    /// URI of a web page with more information about the playlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    /// URI of the playlist itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    /// URI of an image to display for the playlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// URI of the license of the playlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub attribution: Vec<Attribution>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub link: Vec<Link>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub meta: Vec<Meta>,
+    #[serde(default, skip_serializing_if = "PlaylistExtension::is_empty")]
     pub extension: PlaylistExtension,
-    pub creator: String,
-    pub date: String,
-    pub title: String,
+    #[serde(default, deserialize_with = "one_or_many")]
     pub track: Vec<Track>,
-    pub identifier: Option<String>,
+}
+
+impl PlaylistInfo {
+    /// Get the MusicBrainz extension of the playlist, if present.
+    pub fn musicbrainz(&self) -> Option<&MusicBrainzPlaylistExtension> {
+        self.extension.musicbrainz.as_ref()
+    }
+
+    /// Get the MBID of the playlist from its identifier, the URL of the playlist on ListenBrainz.
+    pub fn mbid(&self) -> Option<&str> {
+        let identifier = self.identifier.as_deref()?;
+        let mbid = identifier.trim_end_matches('/').rsplit('/').next()?;
+        Mbid::validate(mbid).ok().map(|_| mbid)
+    }
+}
+
+/// Type of the [`PlaylistInfo::attribution`] field: a playlist that this playlist is derived from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Attribution {
+    Location(String),
+    Identifier(String),
+}
+
+/// Type of the `link` fields: a URI of a resource, with a URI that describes the kind of resource.
+///
+/// In JSPF, this is an object with `rel` as its only key and `content` as its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub rel: String,
+    pub content: String,
+}
+
+/// Type of the `meta` fields: a value, with a URI that describes the kind of value.
+///
+/// In JSPF, this is an object with `rel` as its only key and `content` as its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meta {
+    pub rel: String,
+    pub content: String,
 }
 
 /// Type of the [`PlaylistInfo::extension`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct PlaylistExtension {
-    #[serde(rename = "https://musicbrainz.org/doc/jspf#playlist")]
-    pub musicbrainz: MusicBrainzPlaylistExtension,
+    #[serde(
+        rename = "https://musicbrainz.org/doc/jspf#playlist",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub musicbrainz: Option<MusicBrainzPlaylistExtension>,
+
+    /// Extensions of other applications, keyed by the URI of the application.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl PlaylistExtension {
+    /// Check if there are no extensions at all.
+    pub fn is_empty(&self) -> bool {
+        self.musicbrainz.is_none() && self.other.is_empty()
+    }
 }
 
 /// Type of the [`PlaylistExtension::musicbrainz`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct MusicBrainzPlaylistExtension {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub created_for: Option<String>,
-    pub creator: String,
-    #[serde(default)]
-    pub collaborators: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    /// `None` if the key is missing, so that it is not serialized either.
+    #[serde(
+        default,
+        deserialize_with = "optional_one_or_many",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub collaborators: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub copied_from: Option<String>,
-    #[serde(default)]
-    pub copied_from_deleted: bool,
-    pub public: bool,
-    pub last_modified_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copied_from_deleted: Option<bool>,
+    /// `None` if the key is missing, which ListenBrainz treats as private.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_metadata: Option<AdditionalMetadata>,
+
+    /// Fields that are not known to this library.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
 }
 
 /// Type of the [`MusicBrainzPlaylistExtension::additional_metadata`] field.
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
 pub struct AdditionalMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub algorithm_metadata: Option<AlgorithmMetadata>,
+
+    /// Fields that are not known to this library.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
 }
 
 /// Type of the [`AdditionalMetadata::algorithm_metadata`] field.
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
 pub struct AlgorithmMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_patch: Option<String>,
+
+    /// Fields that are not known to this library.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
 }
 
 /// Type of the [`PlaylistInfo::track`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct Track {
-    pub title: String,
+    /// URIs of the audio of the track.
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub location: Vec<String>,
+    /// URIs that identify the track, e.g. a MusicBrainz recording URI.
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub identifier: Vec<String>,
-    pub creator: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
+    /// URI of a web page with more information about the track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    /// URI of an image to display for the track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// Position of the track on the album, starting at 1.
+    #[serde(rename = "trackNum", default, skip_serializing_if = "Option::is_none")]
+    pub track_num: Option<u32>,
+    /// Duration of the track in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub link: Vec<Link>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub meta: Vec<Meta>,
+    #[serde(default, skip_serializing_if = "TrackExtension::is_empty")]
     pub extension: TrackExtension,
-    pub album: String,
+}
+
+impl Track {
+    /// Get the MusicBrainz extension of the track, if present.
+    pub fn musicbrainz(&self) -> Option<&MusicBrainzTrackExtension> {
+        self.extension.musicbrainz.as_ref()
+    }
+
+    /// Get the MBID of the recording, from the first identifier that is a MusicBrainz recording URI.
+    pub fn recording_mbid(&self) -> Option<&str> {
+        self.identifier
+            .iter()
+            .find(|identifier| identifier.contains("musicbrainz.org/recording/"))
+            .and_then(|identifier| identifier.trim_end_matches('/').rsplit('/').next())
+    }
 }
 
 /// Type of the [`Track::extension`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct TrackExtension {
-    #[serde(rename = "https://musicbrainz.org/doc/jspf#track")]
-    pub musicbrainz: MusicBrainzTrackExtension,
+    #[serde(
+        rename = "https://musicbrainz.org/doc/jspf#track",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub musicbrainz: Option<MusicBrainzTrackExtension>,
+
+    /// Extensions of other applications, keyed by the URI of the application.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl TrackExtension {
+    /// Check if there are no extensions at all.
+    pub fn is_empty(&self) -> bool {
+        self.musicbrainz.is_none() && self.other.is_empty()
+    }
 }
 
 /// Type of the [`TrackExtension::musicbrainz`] field.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct MusicBrainzTrackExtension {
-    pub added_by: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_by: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub artist_mbids: Vec<String>,
-    pub added_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub release_identifier: Option<String>,
+
+    /// Fields that are not known to this library.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+macro_rules! impl_rel_serde {
+    ($name:ident) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(&self.rel, &self.content)?;
+                map.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let map = HashMap::<String, String>::deserialize(deserializer)?;
+                if map.len() != 1 {
+                    return Err(serde::de::Error::invalid_length(
+                        map.len(),
+                        &"an object with a single key",
+                    ));
+                }
+
+                let (rel, content) = map.into_iter().next().unwrap();
+                Ok(Self { rel, content })
+            }
+        }
+    };
+}
+
+impl_rel_serde!(Link);
+impl_rel_serde!(Meta);
+
+/// Deserialize a list that may also be given as a single item or as `null`.
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(optional_one_or_many(deserializer)?.unwrap_or_default())
+}
+
+/// Like [`one_or_many`], but `None` if the list is `null`.
+fn optional_one_or_many<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        Many(Vec<T>),
+        One(T),
+    }
+
+    Ok(
+        Option::<OneOrMany<T>>::deserialize(deserializer)?.map(|items| match items {
+            OneOrMany::Many(items) => items,
+            OneOrMany::One(item) => vec![item],
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn round_trip(value: Value) {
+        let playlist: Playlist = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&playlist).unwrap(), value);
+    }
+
+    #[test]
+    fn round_trips_minimal_playlists() {
+        round_trip(json!({
+            "playlist": {
+                "title": "Mix",
+                "track": [
+                    { "location": ["file:///music/a.flac"] },
+                    { "title": "B", "creator": "Artist", "duration": 180000 },
+                ],
+            }
+        }));
+        round_trip(json!({ "playlist": { "track": [] } }));
+    }
+
+    #[test]
+    fn round_trips_partial_musicbrainz_extensions() {
+        round_trip(json!({
+            "playlist": {
+                "identifier": "https://listenbrainz.org/playlist/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae",
+                "extension": {
+                    "https://musicbrainz.org/doc/jspf#playlist": { "creator": "koen" },
+                },
+                "track": [],
+            }
+        }));
+    }
+
+    #[test]
+    fn round_trips_all_fields_and_unknown_extensions() {
+        round_trip(json!({
+            "playlist": {
+                "title": "Mix",
+                "creator": "koen",
+                "annotation": "Annotation",
+                "info": "https://example.com/info",
+                "location": "https://example.com/mix.jspf",
+                "identifier": "https://listenbrainz.org/playlist/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae",
+                "image": "https://example.com/mix.png",
+                "date": "2024-01-01T00:00:00Z",
+                "license": "https://creativecommons.org/licenses/by/4.0/",
+                "attribution": [{ "identifier": "https://example.com/original" }],
+                "link": [{ "https://example.com/rel": "https://example.com/link" }],
+                "meta": [{ "https://example.com/rel": "value" }],
+                "extension": {
+                    "https://musicbrainz.org/doc/jspf#playlist": {
+                        "created_for": "koen",
+                        "creator": "troi-bot",
+                        "collaborators": ["alice"],
+                        "copied_from_deleted": true,
+                        "public": true,
+                        "unknown": 1,
+                    },
+                    "https://example.com/extension": { "key": "value" },
+                },
+                "track": [{
+                    "identifier": ["https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae"],
+                    "album": "Album",
+                    "trackNum": 3,
+                    "extension": {
+                        "https://musicbrainz.org/doc/jspf#track": {
+                            "added_by": "koen",
+                            "artist_mbids": ["8f3471b5-7e6a-48da-86a9-c1c07a0f47ae"],
+                        },
+                        "https://example.com/extension": [1, 2, 3],
+                    },
+                }],
+            }
+        }));
+    }
+
+    #[test]
+    fn round_trips_listenbrainz_playlists_with_default_values() {
+        // Body of `GET /1/playlist/{playlist_mbid}` for a private playlist that was copied.
+        round_trip(json!({
+            "playlist": {
+                "annotation": "<p>Copy of a weekly exploration playlist.</p>",
+                "creator": "koen",
+                "date": "2024-03-04T10:15:30.123456+00:00",
+                "extension": {
+                    "https://musicbrainz.org/doc/jspf#playlist": {
+                        "additional_metadata": {
+                            "algorithm_metadata": { "source_patch": "weekly-exploration" },
+                        },
+                        "collaborators": [],
+                        "copied_from": "https://listenbrainz.org/playlist/f316bb0e-8e26-44f8-a802-6d2a3688fc7d",
+                        "copied_from_deleted": false,
+                        "creator": "koen",
+                        "last_modified_at": "2024-03-04T10:15:30.123456+00:00",
+                        "public": false,
+                    },
+                },
+                "identifier": "https://listenbrainz.org/playlist/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae",
+                "title": "Copy of Weekly Exploration for koen",
+                "track": [{
+                    "album": "OK Computer",
+                    "creator": "Radiohead",
+                    "duration": 284000,
+                    "extension": {
+                        "https://musicbrainz.org/doc/jspf#track": {
+                            "added_at": "2024-03-04T10:15:30.123456+00:00",
+                            "added_by": "troi-bot",
+                            "additional_metadata": {
+                                "caa_id": 829521842,
+                                "caa_release_mbid": "0b6b4ba0-d36f-47bd-b4ea-6a5b91842d29",
+                            },
+                            "artist_identifiers": [
+                                "https://musicbrainz.org/artist/a74b1b7f-71a5-4011-9441-d0b5e4122711",
+                            ],
+                        },
+                    },
+                    "identifier": ["https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae"],
+                    "title": "Airbag",
+                }],
+            }
+        }));
+    }
+
+    #[test]
+    fn keeps_default_values_of_the_musicbrainz_extension() {
+        let playlist: Playlist = serde_json::from_value(json!({
+            "playlist": {
+                "extension": {
+                    "https://musicbrainz.org/doc/jspf#playlist": {
+                        "collaborators": [],
+                        "copied_from_deleted": false,
+                        "public": false,
+                    },
+                },
+                "track": [],
+            }
+        }))
+        .unwrap();
+
+        let extension = playlist.playlist.musicbrainz().unwrap();
+        assert_eq!(extension.collaborators, Some(Vec::new()));
+        assert_eq!(extension.copied_from_deleted, Some(false));
+        assert_eq!(extension.public, Some(false));
+        assert!(extension.other.is_empty());
+    }
 }
@@ -0,0 +1,612 @@
+//! Iterators that transparently page through API results.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::iter::FusedIterator;
+
+use super::response::UserListensListen;
+use super::{Client, UserName};
+use crate::Error;
+
+/// Iterator over the listens of a user, newest first, across as many pages as needed.
+///
+/// Construct one with [`Client::user_listens_iter`] or
+/// [`ListenBrainz::listens`](crate::ListenBrainz::listens).
+/// Pages are fetched lazily with [`Client::user_listens`], moving `max_ts` back after every page,
+/// until a listen at or before `min_ts` is reached.
+/// Listens that share a timestamp at a page boundary are yielded once.
+/// If more listens share a single timestamp than fit on a page, the API offers no way
+/// to reach the rest of them, and they are skipped; use a larger page size to avoid this.
+///
+/// The iterator yields an error if fetching a page fails, after which it ends.
+///
+/// # Example
+///
+/// ```no_run
+/// # use listenbrainz::raw::{Client, UserName};
+/// let client = Client::new();
+/// let user = UserName::new("koen")?;
+/// for listen in client.user_listens_iter(&user).min_ts(1_700_000_000).page_size(1000) {
+///     let listen = listen?;
+///     println!("{}: {}", listen.listened_at, listen.track_metadata.track_name);
+/// }
+/// # Ok::<(), listenbrainz::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct UserListensIter<'a> {
+    client: &'a Client,
+    user_name: UserName,
+    page_size: Option<u64>,
+    pager: ListensPager,
+    buffer: VecDeque<UserListensListen>,
+}
+
+impl<'a> UserListensIter<'a> {
+    pub(crate) fn new(client: &'a Client, user_name: &UserName) -> Self {
+        Self {
+            client,
+            user_name: user_name.clone(),
+            page_size: None,
+            pager: ListensPager::new(None, None),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Only yield listens with a timestamp greater than `min_ts`.
+    pub fn min_ts(mut self, min_ts: i64) -> Self {
+        self.pager.min_ts = Some(min_ts);
+        self
+    }
+
+    /// Only yield listens with a timestamp less than `max_ts`.
+    pub fn max_ts(mut self, max_ts: i64) -> Self {
+        self.pager.max_ts = Some(max_ts);
+        self
+    }
+
+    /// Set the number of listens fetched per request.
+    /// If not set, the API default is used.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Fetch pages until there are buffered listens or the history has been exhausted.
+    fn fill(&mut self) -> Result<(), Error> {
+        while self.buffer.is_empty() && !self.pager.is_done() {
+            let response = self.client.user_listens(
+                &self.user_name,
+                None,
+                self.pager.max_ts(),
+                self.page_size,
+            )?;
+
+            self.buffer
+                .extend(self.pager.next_page(response.payload.listens));
+        }
+
+        Ok(())
+    }
+
+    /// Stop the iterator after an error.
+    fn stop(&mut self) {
+        self.pager.done = true;
+        self.buffer.clear();
+    }
+}
+
+impl Iterator for UserListensIter<'_> {
+    type Item = Result<UserListensListen, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            self.stop();
+            return Some(Err(error));
+        }
+
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+impl FusedIterator for UserListensIter<'_> {}
+
+/// The state of paging through the listens of a user, newest first, by moving `max_ts` back.
+///
+/// This is shared by [`UserListensIter`] and the asynchronous clients, which fetch the pages
+/// themselves: request a page of listens with [`ListensPager::max_ts`] (and no `min_ts`)
+/// and pass it to [`ListensPager::next_page`], until [`ListensPager::is_done`].
+///
+/// `min_ts` is not sent to the API, because the API returns the listens just after `min_ts`
+/// instead of the newest ones when it is set. Instead, paging stops at the first listen at
+/// or before `min_ts`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ListensPager {
+    min_ts: Option<i64>,
+    max_ts: Option<i64>,
+    /// Timestamp of the oldest listen yielded so far.
+    boundary_ts: Option<i64>,
+    /// Recording MSIDs of the listens yielded with `boundary_ts`.
+    boundary_msids: HashSet<String>,
+    done: bool,
+}
+
+impl ListensPager {
+    pub(crate) fn new(min_ts: Option<i64>, max_ts: Option<i64>) -> Self {
+        Self {
+            min_ts,
+            max_ts,
+            ..Self::default()
+        }
+    }
+
+    /// The `max_ts` to request the next page with.
+    pub(crate) fn max_ts(&self) -> Option<i64> {
+        self.max_ts
+    }
+
+    /// Check if all listens have been paged through.
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Process a page of listens, newest first, and return those that were not returned before.
+    /// The API signals the end of the history with an empty page.
+    pub(crate) fn next_page(&mut self, listens: Vec<UserListensListen>) -> Vec<UserListensListen> {
+        let Some(oldest) = listens.iter().map(|l| l.listened_at).min() else {
+            self.done = true;
+            return Vec::new();
+        };
+
+        let mut new = Vec::new();
+        for listen in listens {
+            if self
+                .min_ts
+                .is_some_and(|min_ts| listen.listened_at <= min_ts)
+            {
+                // Listens are sorted newest first, so the rest are out of bounds as well
+                self.done = true;
+                break;
+            }
+
+            if self.boundary_ts == Some(listen.listened_at) {
+                if !self.boundary_msids.insert(listen.recording_msid.clone()) {
+                    // Already returned at the end of the previous page
+                    continue;
+                }
+            } else {
+                self.boundary_ts = Some(listen.listened_at);
+                self.boundary_msids.clear();
+                self.boundary_msids.insert(listen.recording_msid.clone());
+            }
+            new.push(listen);
+        }
+
+        self.max_ts = if new.is_empty() {
+            // More listens share this timestamp than fit on a page;
+            // skip past it, since the API cannot page within a timestamp.
+            Some(oldest)
+        } else {
+            // `max_ts` is exclusive, so include the oldest timestamp again to get
+            // listens with that timestamp that did not fit on this page.
+            Some(oldest + 1)
+        };
+
+        new
+    }
+}
+
+/// A page of results from an endpoint that is paginated with `count` and `offset`.
+pub(crate) struct Page<T> {
+    /// The items on this page.
+    pub(crate) items: Vec<T>,
+
+    /// The total number of items, if the endpoint reports it.
+    pub(crate) total: Option<u64>,
+}
+
+type FetchPage<'a, T> = dyn FnMut(Option<u64>, u64) -> Result<Option<Page<T>>, Error> + 'a;
+
+/// Iterator over the items of an endpoint that is paginated with `count` and `offset`,
+/// such as the playlists of a user or the statistics endpoints.
+///
+/// Construct one with e.g. [`Client::user_playlists_iter`], [`Client::playlist_search_iter`]
+/// or [`Client::stats_user_artists_iter`].
+/// Pages are fetched lazily, moving `offset` forward until the total number of items
+/// reported by the API has been reached, or until an empty page is returned.
+/// If the API has no statistics for the requested user or range (`204 No Content`),
+/// the iterator is empty.
+///
+/// The iterator yields an error if fetching a page fails, after which it ends.
+///
+/// # Example
+///
+/// ```no_run
+/// # use listenbrainz::raw::request::StatsRange;
+/// # use listenbrainz::raw::{Client, UserName};
+/// let client = Client::new();
+/// let user = UserName::new("koen")?;
+/// let artists = client.stats_user_artists_iter(&user, Some(StatsRange::AllTime));
+/// for artist in artists.page_size(100) {
+///     let artist = artist?;
+///     println!("{}: {}", artist.artist_name, artist.listen_count);
+/// }
+/// # Ok::<(), listenbrainz::Error>(())
+/// ```
+pub struct OffsetIter<'a, T> {
+    fetch: Box<FetchPage<'a, T>>,
+    pager: OffsetPager,
+    buffer: VecDeque<T>,
+}
+
+impl<'a, T> OffsetIter<'a, T> {
+    /// Construct an iterator that fetches pages by calling `fetch` with the page size and offset.
+    pub(crate) fn new(
+        fetch: impl FnMut(Option<u64>, u64) -> Result<Option<Page<T>>, Error> + 'a,
+    ) -> Self {
+        Self {
+            fetch: Box::new(fetch),
+            pager: OffsetPager::new(None),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Set the number of items fetched per request.
+    /// If not set, the API default is used.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.pager.page_size = Some(page_size);
+        self
+    }
+
+    /// Start at the given offset instead of at the first item.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.pager.offset = offset;
+        self
+    }
+
+    /// Fetch the next page if there are no buffered items.
+    fn fill(&mut self) -> Result<(), Error> {
+        if !self.buffer.is_empty() || self.pager.is_done() {
+            return Ok(());
+        }
+
+        let page = (self.fetch)(self.pager.page_size(), self.pager.offset())?;
+        self.buffer.extend(self.pager.next_page(page));
+
+        Ok(())
+    }
+}
+
+impl<T> Iterator for OffsetIter<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            self.pager.done = true;
+            self.buffer.clear();
+            return Some(Err(error));
+        }
+
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+impl<T> FusedIterator for OffsetIter<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for OffsetIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OffsetIter")
+            .field("pager", &self.pager)
+            .field("buffer", &self.buffer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The state of paging through the items of an endpoint that is paginated with `count`
+/// and `offset`.
+///
+/// This is shared by [`OffsetIter`] and the asynchronous clients, which fetch the pages
+/// themselves: request a page with [`OffsetPager::page_size`] and [`OffsetPager::offset`]
+/// and pass it to [`OffsetPager::next_page`], until [`OffsetPager::is_done`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OffsetPager {
+    page_size: Option<u64>,
+    offset: u64,
+    done: bool,
+}
+
+impl OffsetPager {
+    pub(crate) fn new(page_size: Option<u64>) -> Self {
+        Self {
+            page_size,
+            ..Self::default()
+        }
+    }
+
+    /// The number of items to request the next page with.
+    pub(crate) fn page_size(&self) -> Option<u64> {
+        self.page_size
+    }
+
+    /// The offset to request the next page with.
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Check if all items have been paged through.
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Process a page and return its items. `None` means that the API has no items at all
+    /// (`204 No Content`). Paging ends once the total number of items reported by the API
+    /// has been reached, or at an empty page.
+    pub(crate) fn next_page<T>(&mut self, page: Option<Page<T>>) -> Vec<T> {
+        let Some(page) = page else {
+            self.done = true;
+            return Vec::new();
+        };
+
+        self.offset += page.items.len() as u64;
+        if page.items.is_empty() || page.total.is_some_and(|total| self.offset >= total) {
+            self.done = true;
+        }
+
+        page.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::raw::ids::testing::user;
+    use crate::raw::transport::mock::MockTransport;
+    use crate::raw::transport::{HttpRequest, HttpResponse};
+
+    /// A stand-in for `GET /1/user/(user_name)/listens` that serves `history`,
+    /// a list of `(listened_at, recording_msid)` pairs, newest first.
+    fn listens_server(history: &[(i64, &str)]) -> MockTransport {
+        let history: Vec<(i64, String)> = history
+            .iter()
+            .map(|(ts, msid)| (*ts, msid.to_string()))
+            .collect();
+
+        MockTransport::new(move |request: &HttpRequest| {
+            let param = |name: &str| {
+                request
+                    .params
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.parse::<i64>().unwrap())
+            };
+            let count = param("count").unwrap_or(25) as usize;
+            let in_range = |(ts, _): &&(i64, String)| {
+                param("max_ts").is_none_or(|max_ts| *ts < max_ts)
+                    && param("min_ts").is_none_or(|min_ts| *ts > min_ts)
+            };
+
+            let mut page: Vec<_> = history.iter().filter(in_range).collect();
+            if param("min_ts").is_some() {
+                // With `min_ts`, the API returns the listens just after it
+                page = page.split_off(page.len().saturating_sub(count));
+            }
+            page.truncate(count);
+
+            let listens: Vec<_> = page
+                .iter()
+                .map(|(ts, msid)| {
+                    json!({
+                        "user_name": "koen",
+                        "inserted_at": ts,
+                        "listened_at": ts,
+                        "recording_msid": msid,
+                        "track_metadata": {
+                            "artist_name": "artist",
+                            "track_name": msid,
+                            "release_name": null,
+                            "additional_info": {},
+                            "mbid_mapping": null,
+                        },
+                    })
+                })
+                .collect();
+            let body = json!({
+                "payload": {
+                    "count": listens.len(),
+                    "latest_listen_ts": 0,
+                    "oldest_listen_ts": 0,
+                    "user_id": "koen",
+                    "listens": listens,
+                }
+            });
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+
+    fn collect(iter: UserListensIter) -> Vec<(i64, String)> {
+        iter.map(|listen| {
+            let listen = listen.unwrap();
+            (listen.listened_at, listen.recording_msid)
+        })
+        .collect()
+    }
+
+    fn owned(listens: &[(i64, &str)]) -> Vec<(i64, String)> {
+        listens
+            .iter()
+            .map(|(ts, msid)| (*ts, msid.to_string()))
+            .collect()
+    }
+
+    const HISTORY: &[(i64, &str)] = &[
+        (50, "a"),
+        (40, "b"),
+        (40, "c"),
+        (40, "d"),
+        (30, "e"),
+        (20, "f"),
+        (20, "g"),
+        (10, "h"),
+    ];
+
+    #[test]
+    fn yields_the_whole_history() {
+        let client = Client::new_with_transport("http://localhost/1/", listens_server(HISTORY));
+        // Page sizes that fit all listens that share a timestamp
+        for page_size in [3, 4, 5, 100] {
+            let listens = collect(client.user_listens_iter(&user("koen")).page_size(page_size));
+            assert_eq!(listens, owned(HISTORY), "page size {page_size}");
+        }
+    }
+
+    #[test]
+    fn yields_listens_sharing_a_timestamp_across_a_page_boundary_once() {
+        let transport = listens_server(HISTORY);
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        // The first page ends in the middle of the listens at 40
+        let listens = collect(client.user_listens_iter(&user("koen")).page_size(3));
+        assert_eq!(listens, owned(HISTORY));
+
+        let max_ts: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|request| {
+                request
+                    .params
+                    .iter()
+                    .find(|(key, _)| key == "max_ts")
+                    .map(|(_, value)| value.clone())
+            })
+            .collect();
+        assert_eq!(max_ts[0], None);
+        assert_eq!(max_ts[1].as_deref(), Some("41"));
+    }
+
+    #[test]
+    fn skips_listens_sharing_a_timestamp_beyond_a_page() {
+        let history = [(50, "a"), (40, "b"), (40, "c"), (40, "d"), (30, "e")];
+        let client = Client::new_with_transport("http://localhost/1/", listens_server(&history));
+
+        let listens = collect(client.user_listens_iter(&user("koen")).page_size(2));
+        assert_eq!(
+            listens,
+            owned(&[(50, "a"), (40, "b"), (40, "c"), (30, "e")])
+        );
+    }
+
+    #[test]
+    fn respects_min_ts_and_max_ts() {
+        let transport = listens_server(HISTORY);
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        let listens = collect(
+            client
+                .user_listens_iter(&user("koen"))
+                .min_ts(20)
+                .page_size(3),
+        );
+        assert_eq!(listens, owned(&HISTORY[..5]));
+
+        let listens = collect(
+            client
+                .user_listens_iter(&user("koen"))
+                .max_ts(40)
+                .page_size(3),
+        );
+        assert_eq!(listens, owned(&HISTORY[4..]));
+
+        let listens = collect(
+            client
+                .user_listens_iter(&user("koen"))
+                .min_ts(10)
+                .max_ts(50)
+                .page_size(3),
+        );
+        assert_eq!(listens, owned(&HISTORY[1..7]));
+
+        // `min_ts` is applied locally, since the API would return the oldest listens first
+        for request in transport.requests() {
+            assert!(request.params.iter().all(|(key, _)| key != "min_ts"));
+        }
+    }
+
+    #[test]
+    fn stops_at_min_ts_without_fetching_more_pages() {
+        let transport = listens_server(HISTORY);
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        let listens = collect(
+            client
+                .user_listens_iter(&user("koen"))
+                .min_ts(45)
+                .page_size(3),
+        );
+        assert_eq!(listens, owned(&HISTORY[..1]));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn yields_nothing_for_an_empty_history() {
+        let transport = listens_server(&[]);
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        assert!(collect(client.user_listens_iter(&user("koen"))).is_empty());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn ends_after_an_error() {
+        let transport =
+            MockTransport::new(|_| Ok(HttpResponse::new(500, r#"{"code": 500, "error": "x"}"#)));
+        let client = Client::new_with_transport("http://localhost/1/", transport.clone());
+
+        let mut listens = client.user_listens_iter(&user("koen"));
+        assert!(listens.next().unwrap().is_err());
+        assert!(listens.next().is_none());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    /// Page through `items` with an [`OffsetIter`], recording the requested offsets.
+    fn offset_pages(items: u64, total: Option<u64>, page_size: u64) -> (Vec<u64>, Vec<u64>) {
+        let mut offsets = Vec::new();
+        let collected = OffsetIter::new(|count, offset| {
+            offsets.push(offset);
+            let end = (offset + count.unwrap()).min(items);
+            Ok(Some(Page {
+                items: (offset..end).collect(),
+                total,
+            }))
+        })
+        .page_size(page_size)
+        .collect::<Result<Vec<_>, Error>>()
+        .unwrap();
+        (collected, offsets)
+    }
+
+    #[test]
+    fn offset_iter_stops_at_the_total() {
+        let (items, offsets) = offset_pages(5, Some(5), 2);
+        assert_eq!(items, [0, 1, 2, 3, 4]);
+        assert_eq!(offsets, [0, 2, 4]);
+
+        let (items, offsets) = offset_pages(4, Some(4), 2);
+        assert_eq!(items, [0, 1, 2, 3]);
+        assert_eq!(offsets, [0, 2]);
+    }
+
+    #[test]
+    fn offset_iter_stops_at_an_empty_page() {
+        let (items, offsets) = offset_pages(4, None, 2);
+        assert_eq!(items, [0, 1, 2, 3]);
+        assert_eq!(offsets, [0, 2, 4]);
+    }
+
+    #[test]
+    fn offset_pager_stops_without_content() {
+        let mut pager = OffsetPager::new(Some(10));
+        assert!(pager.next_page::<u64>(None).is_empty());
+        assert!(pager.is_done());
+    }
+}
@@ -6,7 +6,7 @@ use std::borrow::Borrow;
 
 use serde::{Deserialize, Serialize};
 
-use super::jspf::AdditionalMetadata;
+use super::jspf::{AdditionalMetadata, PlaylistInfo};
 
 // --------- submit-listens
 
@@ -96,6 +96,8 @@ pub struct PlaylistCreatePlaylist {
 ///     identifier: "https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae".to_string() // ✔️ Valid!
 /// };
 /// ```
+///
+/// [`PlaylistBuilder`](crate::PlaylistBuilder) builds these from bare recording MBIDs.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct PlaylistCreatePlaylistTrack {
     pub identifier: String,
@@ -121,6 +123,319 @@ pub struct PlaylistCreatePlaylistExtensionInner {
     pub additional_metadata: Option<AdditionalMetadata>,
 }
 
+// --------- playlist/{playlist_mbid}/item/add
+
+/// Request type for [`Client::playlist_item_add`](super::Client::playlist_item_add).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistItemAdd {
+    pub playlist: PlaylistItemAddPlaylist,
+}
+
+/// Inner type for [`PlaylistItemAdd`].
+///
+/// The identifiers of the tracks must be MusicBrainz URIs; see [`PlaylistCreatePlaylistTrack`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistItemAddPlaylist {
+    pub track: Vec<PlaylistCreatePlaylistTrack>,
+}
+
+// --------- playlist/{playlist_mbid}/item/move
+
+/// Request type for [`Client::playlist_item_move`](super::Client::playlist_item_move).
+///
+/// Moves `count` tracks, starting with the recording `mbid` at position `from`, to position `to`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistItemMove {
+    pub mbid: String,
+    pub from: u64,
+    pub to: u64,
+    pub count: u64,
+}
+
+// --------- playlist/{playlist_mbid}/item/delete
+
+/// Request type for [`Client::playlist_item_delete`](super::Client::playlist_item_delete).
+///
+/// Deletes `count` tracks, starting at position `index`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistItemDelete {
+    pub index: u64,
+    pub count: u64,
+}
+
+// --------- playlist/edit/{playlist_mbid}
+
+/// Request type for [`Client::playlist_edit`](super::Client::playlist_edit).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistEdit {
+    pub playlist: PlaylistEditPlaylist,
+}
+
+/// Inner type for [`PlaylistEdit`].
+///
+/// Fields that are `None` are not sent, and are left unchanged.
+/// Use [`PlaylistEditPlaylist::from`] with a [`PlaylistInfo`] to start from the current state of a playlist.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistEditPlaylist {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
+    pub extension: PlaylistEditPlaylistExtension,
+}
+
+/// The extension of [`PlaylistEditPlaylist`]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistEditPlaylistExtension {
+    #[serde(rename = "https://musicbrainz.org/doc/jspf#playlist")]
+    pub musicbrainz: PlaylistEditPlaylistExtensionInner,
+}
+
+/// Inner part of [`PlaylistEditPlaylistExtension`]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlaylistEditPlaylistExtensionInner {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collaborators: Option<Vec<String>>,
+}
+
+impl From<&PlaylistInfo> for PlaylistEditPlaylist {
+    fn from(playlist: &PlaylistInfo) -> Self {
+        Self {
+            title: playlist.title.clone(),
+            annotation: playlist.annotation.clone(),
+            extension: PlaylistEditPlaylistExtension {
+                musicbrainz: PlaylistEditPlaylistExtensionInner {
+                    public: playlist
+                        .musicbrainz()
+                        .and_then(|extension| extension.public),
+                    collaborators: playlist
+                        .musicbrainz()
+                        .and_then(|extension| extension.collaborators.clone()),
+                },
+            },
+        }
+    }
+}
+
+// --------- stats range
+
+/// Time range of statistics, used as the `range` parameter of the statistics endpoints
+/// and in the `range`/`stats_range` fields of their responses.
+///
+/// Ranges that the API adds in the future are represented by [`StatsRange::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StatsRange {
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    HalfYearly,
+    AllTime,
+    /// A range that is not known to this library.
+    Other(String),
+}
+
+impl StatsRange {
+    /// Get the name of this range as used by the API, e.g. `"this_week"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ThisWeek => "this_week",
+            Self::ThisMonth => "this_month",
+            Self::ThisYear => "this_year",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+            Self::Year => "year",
+            Self::HalfYearly => "half_yearly",
+            Self::AllTime => "all_time",
+            Self::Other(range) => range,
+        }
+    }
+}
+
+impl From<&str> for StatsRange {
+    fn from(range: &str) -> Self {
+        match range {
+            "this_week" => Self::ThisWeek,
+            "this_month" => Self::ThisMonth,
+            "this_year" => Self::ThisYear,
+            "week" => Self::Week,
+            "month" => Self::Month,
+            "quarter" => Self::Quarter,
+            "year" => Self::Year,
+            "half_yearly" => Self::HalfYearly,
+            "all_time" => Self::AllTime,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for StatsRange {
+    fn from(range: String) -> Self {
+        match Self::from(range.as_str()) {
+            Self::Other(_) => Self::Other(range),
+            known => known,
+        }
+    }
+}
+
+impl std::str::FromStr for StatsRange {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl std::fmt::Display for StatsRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for StatsRange {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for StatsRange {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+// --------- music service
+
+/// A streaming service that ListenBrainz playlists can be exported to and imported from,
+/// used in the path of the playlist export and import endpoints.
+///
+/// The service must be linked to the ListenBrainz account of the user whose token is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MusicService {
+    #[serde(rename = "spotify")]
+    Spotify,
+    #[serde(rename = "apple_music")]
+    AppleMusic,
+    #[serde(rename = "soundcloud")]
+    SoundCloud,
+}
+
+impl MusicService {
+    /// Get the name of this service as used by the API, e.g. `"apple_music"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spotify => "spotify",
+            Self::AppleMusic => "apple_music",
+            Self::SoundCloud => "soundcloud",
+        }
+    }
+}
+
+impl std::fmt::Display for MusicService {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// --------- metadata/submit_manual_mapping
+
+/// Request type for [`Client::metadata_submit_manual_mapping`](super::Client::metadata_submit_manual_mapping).
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataSubmitManualMapping<T: StrType> {
+    pub recording_msid: T,
+    pub recording_mbid: T,
+}
+
+// --------- metadata include
+
+/// Additional metadata to include in the responses of the metadata endpoints,
+/// used in the `inc` parameter of e.g. [`Client::metadata_recording`](super::Client::metadata_recording).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataInclude {
+    /// The artist credit and the artists in it.
+    Artist,
+    /// The tags and genres of the recording, release group and artists.
+    Tag,
+    /// The release (or, for artists, the release groups).
+    Release,
+}
+
+impl MetadataInclude {
+    /// Get the name of this option as used by the API, e.g. `"artist"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Artist => "artist",
+            Self::Tag => "tag",
+            Self::Release => "release",
+        }
+    }
+
+    /// Join options into the value of the `inc` parameter, separated by spaces.
+    pub(crate) fn join(inc: &[Self]) -> String {
+        inc.iter().map(Self::as_str).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl std::fmt::Display for MetadataInclude {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// --------- recommendation/feedback
+
+/// Request type for [`Client::recommendation_feedback_submit`](super::Client::recommendation_feedback_submit).
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct RecommendationFeedbackSubmit<T: StrType> {
+    pub recording_mbid: T,
+    pub rating: RecommendationFeedbackRating,
+}
+
+/// Request type for [`Client::recommendation_feedback_delete`](super::Client::recommendation_feedback_delete).
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct RecommendationFeedbackDelete<T: StrType> {
+    pub recording_mbid: T,
+}
+
+/// A rating of a recommended recording, submitted with
+/// [`Client::recommendation_feedback_submit`](super::Client::recommendation_feedback_submit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationFeedbackRating {
+    Love,
+    Like,
+    Dislike,
+    Hate,
+    /// The recording does not fit the taste of the user, regardless of whether they like it.
+    BadRecommendation,
+}
+
+impl RecommendationFeedbackRating {
+    /// Get the name of this rating as used by the API, e.g. `"bad_recommendation"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Love => "love",
+            Self::Like => "like",
+            Self::Dislike => "dislike",
+            Self::Hate => "hate",
+            Self::BadRecommendation => "bad_recommendation",
+        }
+    }
+}
+
+impl std::fmt::Display for RecommendationFeedbackRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Type for use in generic contexts that want a string. Technically, only [Serialize] is required by the api,
 /// but the [Borrow] constraint makes working with values more convenient in non-write contexts.
 pub trait StrType: Borrow<str> + Serialize {}
@@ -1,3 +1,558 @@
-//TODO: https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html
-// Currently no api responses have been made for this page.
-// The header comments will be made as needed
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::raw::response::{
+    body_response_type, response_type, HttpResponse, RateLimit, ResponseType,
+};
+use crate::Error;
+
+// --------- GET /1/metadata/recording/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-recording-
+
+body_response_type! {
+    /// Response type for [`Client::metadata_recording`](super::Client::metadata_recording).
+    ///
+    /// The API responds with an object that maps the MBIDs of the recordings to their metadata.
+    /// Recordings that are not known to ListenBrainz are left out.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct MetadataRecordingResponse {
+        pub recordings: HashMap<String, RecordingMetadata>,
+    }
+}
+
+/// Type of the [`MetadataRecordingResponse::recordings`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RecordingMetadata {
+    pub recording: MetadataRecording,
+    /// Included with [`MetadataInclude::Artist`](crate::raw::request::MetadataInclude::Artist).
+    pub artist: Option<MetadataArtistCredit>,
+    /// Included with [`MetadataInclude::Release`](crate::raw::request::MetadataInclude::Release).
+    pub release: Option<MetadataRelease>,
+    /// Included with [`MetadataInclude::Tag`](crate::raw::request::MetadataInclude::Tag).
+    pub tag: Option<MetadataTags>,
+}
+
+/// Type of the [`RecordingMetadata::recording`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataRecording {
+    pub name: String,
+    /// Length of the recording in milliseconds.
+    pub length: Option<u64>,
+    #[serde(default)]
+    pub rels: Vec<MetadataRel>,
+}
+
+/// A relationship of a recording or release group with an artist, e.g. a performer.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataRel {
+    #[serde(rename = "type")]
+    pub rel_type: String,
+    pub artist_mbid: Option<String>,
+    pub artist_name: Option<String>,
+    pub instrument: Option<String>,
+}
+
+/// The artist credit of a recording or release group.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataArtistCredit {
+    /// The name of the artist credit, e.g. `"Artist A feat. Artist B"`.
+    pub name: String,
+    pub artist_credit_id: Option<i64>,
+    #[serde(default)]
+    pub artists: Vec<MetadataArtist>,
+}
+
+/// An artist, in an artist credit or returned by
+/// [`Client::metadata_artist`](super::Client::metadata_artist).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataArtist {
+    pub name: String,
+    pub artist_mbid: Option<String>,
+    /// The phrase that joins this artist with the next one in an artist credit, e.g. `" feat. "`.
+    pub join_phrase: Option<String>,
+    #[serde(rename = "type")]
+    pub artist_type: Option<String>,
+    pub gender: Option<String>,
+    pub area: Option<String>,
+    pub begin_year: Option<i32>,
+    pub end_year: Option<i32>,
+    /// Links of the artist, by type, e.g. `"wikidata"` or `"official homepage"`.
+    #[serde(default)]
+    pub rels: HashMap<String, String>,
+}
+
+/// The release of a recording.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataRelease {
+    pub name: Option<String>,
+    pub mbid: Option<String>,
+    pub release_group_mbid: Option<String>,
+    pub album_artist_name: Option<String>,
+    pub year: Option<i32>,
+    pub caa_id: Option<i64>,
+    pub caa_release_mbid: Option<String>,
+}
+
+/// The tags of a recording or release group and its artists.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct MetadataTags {
+    #[serde(default)]
+    pub recording: Vec<MetadataTag>,
+    #[serde(default)]
+    pub release_group: Vec<MetadataTag>,
+    #[serde(default)]
+    pub artist: Vec<MetadataTag>,
+}
+
+/// Type of the fields of [`MetadataTags`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataTag {
+    pub tag: String,
+    /// The number of votes for the tag.
+    pub count: i64,
+    /// Set if the tag is a genre.
+    pub genre_mbid: Option<String>,
+    /// Set for the tags of artists.
+    pub artist_mbid: Option<String>,
+}
+
+// --------- GET /1/metadata/release_group/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-release_group-
+
+body_response_type! {
+    /// Response type for [`Client::metadata_release_group`](super::Client::metadata_release_group).
+    ///
+    /// The API responds with an object that maps the MBIDs of the release groups to their metadata.
+    /// Release groups that are not known to ListenBrainz are left out.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct MetadataReleaseGroupResponse {
+        pub release_groups: HashMap<String, ReleaseGroupMetadata>,
+    }
+}
+
+/// Type of the [`MetadataReleaseGroupResponse::release_groups`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ReleaseGroupMetadata {
+    pub release_group: MetadataReleaseGroup,
+    /// Included with [`MetadataInclude::Artist`](crate::raw::request::MetadataInclude::Artist).
+    pub artist: Option<MetadataArtistCredit>,
+    /// Included with [`MetadataInclude::Tag`](crate::raw::request::MetadataInclude::Tag).
+    pub tag: Option<MetadataTags>,
+}
+
+/// A release group, returned by [`Client::metadata_release_group`](super::Client::metadata_release_group)
+/// or as part of [`ArtistMetadata`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataReleaseGroup {
+    pub name: Option<String>,
+    pub mbid: Option<String>,
+    /// Date of the first release, e.g. `"1987-11-12"`, `"1987-11"` or `"1987"`.
+    pub date: Option<String>,
+    /// The primary type, e.g. `"Album"` or `"Single"`.
+    #[serde(rename = "type")]
+    pub release_group_type: Option<String>,
+    pub caa_id: Option<i64>,
+    pub caa_release_mbid: Option<String>,
+    #[serde(default)]
+    pub rels: Vec<MetadataRel>,
+}
+
+// --------- GET /1/metadata/artist/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-artist-
+
+body_response_type! {
+    /// Response type for [`Client::metadata_artist`](super::Client::metadata_artist).
+    ///
+    /// The API responds with the list of artists.
+    /// Artists that are not known to ListenBrainz are left out.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct MetadataArtistResponse {
+        pub artists: Vec<ArtistMetadata>,
+    }
+}
+
+/// Type of the [`MetadataArtistResponse::artists`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ArtistMetadata {
+    #[serde(flatten)]
+    pub artist: MetadataArtist,
+    /// Included with [`MetadataInclude::Tag`](crate::raw::request::MetadataInclude::Tag).
+    pub tag: Option<MetadataTags>,
+    /// Included with [`MetadataInclude::Release`](crate::raw::request::MetadataInclude::Release).
+    #[serde(default)]
+    pub release_group: Vec<MetadataReleaseGroup>,
+}
+
+// --------- GET /1/metadata/lookup/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-lookup-
+
+response_type! {
+    /// Response type for [`Client::metadata_lookup`](super::Client::metadata_lookup).
+    ///
+    /// All fields are [`None`] if no recording was found.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct MetadataLookupResponse {
+        pub recording_mbid: Option<String>,
+        pub recording_name: Option<String>,
+        pub artist_credit_name: Option<String>,
+        pub artist_mbids: Option<Vec<String>>,
+        pub release_mbid: Option<String>,
+        pub release_name: Option<String>,
+        pub metadata: Option<RecordingMetadata>,
+    }
+}
+
+// --------- POST /1/metadata/submit_manual_mapping/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#post--1-metadata-submit_manual_mapping-
+
+response_type! {
+    /// Response type for [`Client::metadata_submit_manual_mapping`](super::Client::metadata_submit_manual_mapping).
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct MetadataSubmitManualMappingResponse {
+        pub status: String,
+    }
+}
+
+// --------- GET /1/metadata/get_manual_mapping/
+// https://listenbrainz.readthedocs.io/en/latest/users/api/metadata.html#get--1-metadata-get_manual_mapping-
+
+/// Response type for [`Client::metadata_get_manual_mapping`](super::Client::metadata_get_manual_mapping).
+///
+/// [`MetadataGetManualMappingResponse::mapping`] is [`None`] if the user has not submitted
+/// a mapping for the recording MSID, which the API reports with `404 Not Found`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataGetManualMappingResponse {
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimit>,
+    #[serde(skip)]
+    pub attempts: u32,
+    pub status: String,
+    pub mapping: Option<MetadataManualMapping>,
+}
+
+impl ResponseType for MetadataGetManualMappingResponse {
+    fn from_response(response: HttpResponse) -> Result<Self, Error> {
+        let rate_limit = RateLimit::from_headers(&response);
+
+        // No mapping is reported as 404 with `{"status": "none"}` instead of an error message
+        let mut result: Self = match response.json() {
+            Ok(result) if response.status == 404 => result,
+            _ => Error::try_from_error_response(response)?.json()?,
+        };
+        result.rate_limit = rate_limit;
+        result.attempts = 1;
+        Ok(result)
+    }
+
+    fn set_attempts(&mut self, attempts: u32) {
+        self.attempts = attempts;
+    }
+}
+
+/// Type of the [`MetadataGetManualMappingResponse::mapping`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MetadataManualMapping {
+    pub recording_msid: String,
+    pub recording_mbid: String,
+    pub user_id: i64,
+    /// When the mapping was submitted, in ISO 8601 format.
+    pub created: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRANGERS: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+    const ROADS: &str = "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3";
+    const DUMMY: &str = "48140466-cff6-3222-bd55-63c27e43190d";
+    const PORTISHEAD: &str = "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11";
+
+    /// A `GET /1/metadata/recording/` response with `inc=artist tag release`.
+    const RECORDINGS: &str = r#"{
+        "e97f805a-ab48-4c52-855e-07049142113d": {
+            "artist": {
+                "artist_credit_id": 65,
+                "artists": [
+                    {
+                        "area": "United Kingdom",
+                        "artist_mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                        "begin_year": 1991,
+                        "join_phrase": "",
+                        "name": "Portishead",
+                        "rels": {
+                            "official homepage": "http://www.portishead.co.uk/",
+                            "wikidata": "https://www.wikidata.org/wiki/Q191352"
+                        },
+                        "type": "Group"
+                    }
+                ],
+                "name": "Portishead"
+            },
+            "recording": {
+                "length": 237000,
+                "name": "Strangers",
+                "rels": [
+                    {
+                        "artist_mbid": "c2a4c3c3-b8a6-4e4e-8f5e-9a1ef4a4a0d1",
+                        "artist_name": "Adrian Utley",
+                        "instrument": "guitar",
+                        "type": "instrument"
+                    },
+                    {
+                        "artist_mbid": "a2f3d1c1-4b5e-4a2f-9c1e-1d0e2f3a4b5c",
+                        "artist_name": "Beth Gibbons",
+                        "type": "vocal"
+                    }
+                ]
+            },
+            "release": {
+                "album_artist_name": "Portishead",
+                "caa_id": 829521842,
+                "caa_release_mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+                "mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+                "name": "Dummy",
+                "release_group_mbid": "48140466-cff6-3222-bd55-63c27e43190d",
+                "year": 1994
+            },
+            "tag": {
+                "artist": [
+                    {
+                        "artist_mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                        "count": 18,
+                        "genre_mbid": "45eb1d9c-588c-4dc8-9394-a14b7c4f02bc",
+                        "tag": "trip hop"
+                    }
+                ],
+                "recording": [
+                    {
+                        "count": 1,
+                        "genre_mbid": "45eb1d9c-588c-4dc8-9394-a14b7c4f02bc",
+                        "tag": "trip hop"
+                    },
+                    {
+                        "count": 1,
+                        "tag": "bristol"
+                    }
+                ],
+                "release_group": []
+            }
+        },
+        "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3": {
+            "recording": {
+                "length": null,
+                "name": "Roads"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parses_recordings_keyed_by_mbid() {
+        let response =
+            MetadataRecordingResponse::from_response(HttpResponse::new(200, RECORDINGS)).unwrap();
+        assert_eq!(response.recordings.len(), 2);
+
+        let strangers = &response.recordings[STRANGERS];
+        assert_eq!(strangers.recording.name, "Strangers");
+        assert_eq!(strangers.recording.length, Some(237000));
+        assert_eq!(strangers.recording.rels.len(), 2);
+        assert_eq!(strangers.recording.rels[0].rel_type, "instrument");
+        assert_eq!(
+            strangers.recording.rels[0].instrument.as_deref(),
+            Some("guitar")
+        );
+        assert_eq!(strangers.recording.rels[1].instrument, None);
+
+        let artist = strangers.artist.as_ref().unwrap();
+        assert_eq!(artist.name, "Portishead");
+        assert_eq!(artist.artist_credit_id, Some(65));
+        assert_eq!(artist.artists[0].artist_mbid.as_deref(), Some(PORTISHEAD));
+        assert_eq!(artist.artists[0].artist_type.as_deref(), Some("Group"));
+        assert_eq!(artist.artists[0].begin_year, Some(1991));
+        assert_eq!(artist.artists[0].end_year, None);
+        assert_eq!(
+            artist.artists[0].rels["wikidata"],
+            "https://www.wikidata.org/wiki/Q191352"
+        );
+
+        let release = strangers.release.as_ref().unwrap();
+        assert_eq!(release.name.as_deref(), Some("Dummy"));
+        assert_eq!(release.release_group_mbid.as_deref(), Some(DUMMY));
+        assert_eq!(release.year, Some(1994));
+        assert_eq!(release.caa_id, Some(829521842));
+
+        let tag = strangers.tag.as_ref().unwrap();
+        assert_eq!(tag.artist[0].artist_mbid.as_deref(), Some(PORTISHEAD));
+        assert_eq!(tag.recording.len(), 2);
+        assert_eq!(tag.recording[1].tag, "bristol");
+        assert_eq!(tag.recording[1].genre_mbid, None);
+        assert!(tag.release_group.is_empty());
+    }
+
+    #[test]
+    fn leaves_out_sections_that_were_not_included() {
+        let response =
+            MetadataRecordingResponse::from_response(HttpResponse::new(200, RECORDINGS)).unwrap();
+
+        let roads = &response.recordings[ROADS];
+        assert_eq!(
+            roads.recording,
+            MetadataRecording {
+                name: "Roads".to_string(),
+                length: None,
+                rels: Vec::new(),
+            }
+        );
+        assert_eq!(roads.artist, None);
+        assert_eq!(roads.release, None);
+        assert_eq!(roads.tag, None);
+
+        // Unknown MBIDs are left out of the response
+        let response = MetadataRecordingResponse::from_response(HttpResponse::new(200, "{}"));
+        assert!(response.unwrap().recordings.is_empty());
+    }
+
+    #[test]
+    fn parses_release_groups_keyed_by_mbid() {
+        let body = r#"{
+            "48140466-cff6-3222-bd55-63c27e43190d": {
+                "artist": {
+                    "artist_credit_id": 65,
+                    "artists": [
+                        {
+                            "artist_mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                            "join_phrase": "",
+                            "name": "Portishead"
+                        }
+                    ],
+                    "name": "Portishead"
+                },
+                "release_group": {
+                    "caa_id": 829521842,
+                    "caa_release_mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+                    "date": "1994-08-22",
+                    "name": "Dummy",
+                    "rels": [],
+                    "type": "Album"
+                },
+                "tag": {
+                    "artist": [],
+                    "release_group": [
+                        {
+                            "count": 7,
+                            "genre_mbid": "45eb1d9c-588c-4dc8-9394-a14b7c4f02bc",
+                            "tag": "trip hop"
+                        }
+                    ]
+                }
+            }
+        }"#;
+        let response =
+            MetadataReleaseGroupResponse::from_response(HttpResponse::new(200, body)).unwrap();
+
+        let dummy = &response.release_groups[DUMMY];
+        assert_eq!(dummy.release_group.name.as_deref(), Some("Dummy"));
+        assert_eq!(dummy.release_group.date.as_deref(), Some("1994-08-22"));
+        assert_eq!(
+            dummy.release_group.release_group_type.as_deref(),
+            Some("Album")
+        );
+        assert_eq!(dummy.release_group.mbid, None);
+        assert_eq!(dummy.artist.as_ref().unwrap().artists[0].name, "Portishead");
+
+        let tag = dummy.tag.as_ref().unwrap();
+        assert_eq!(tag.release_group[0].count, 7);
+        assert!(tag.recording.is_empty());
+    }
+
+    #[test]
+    fn parses_artists() {
+        let body = r#"[
+            {
+                "area": "United Kingdom",
+                "artist_mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                "begin_year": 1991,
+                "mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                "name": "Portishead",
+                "rels": {
+                    "wikidata": "https://www.wikidata.org/wiki/Q191352"
+                },
+                "release_group": [
+                    {
+                        "caa_id": 829521842,
+                        "caa_release_mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+                        "date": "1994-08-22",
+                        "mbid": "48140466-cff6-3222-bd55-63c27e43190d",
+                        "name": "Dummy",
+                        "type": "Album"
+                    }
+                ],
+                "tag": {
+                    "artist": [
+                        {
+                            "artist_mbid": "8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11",
+                            "count": 18,
+                            "tag": "trip hop"
+                        }
+                    ]
+                },
+                "type": "Group"
+            },
+            {
+                "artist_mbid": "a2f3d1c1-4b5e-4a2f-9c1e-1d0e2f3a4b5c",
+                "name": "Beth Gibbons"
+            }
+        ]"#;
+        let response = MetadataArtistResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(response.artists.len(), 2);
+
+        let portishead = &response.artists[0];
+        assert_eq!(portishead.artist.name, "Portishead");
+        assert_eq!(portishead.artist.area.as_deref(), Some("United Kingdom"));
+        assert_eq!(portishead.artist.artist_type.as_deref(), Some("Group"));
+        assert_eq!(portishead.release_group[0].mbid.as_deref(), Some(DUMMY));
+        assert_eq!(portishead.tag.as_ref().unwrap().artist[0].tag, "trip hop");
+
+        let beth = &response.artists[1];
+        assert_eq!(beth.artist.name, "Beth Gibbons");
+        assert!(beth.artist.rels.is_empty());
+        assert!(beth.release_group.is_empty());
+        assert_eq!(beth.tag, None);
+    }
+
+    #[test]
+    fn parses_lookups() {
+        let body = r#"{
+            "artist_credit_name": "Portishead",
+            "artist_mbids": ["8f6bd1e4-fbe1-4f50-aa9b-94c450ec0f11"],
+            "metadata": {
+                "recording": {"length": 237000, "name": "Strangers", "rels": []},
+                "release": {
+                    "album_artist_name": "Portishead",
+                    "mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+                    "name": "Dummy",
+                    "release_group_mbid": "48140466-cff6-3222-bd55-63c27e43190d",
+                    "year": 1994
+                }
+            },
+            "recording_mbid": "e97f805a-ab48-4c52-855e-07049142113d",
+            "recording_name": "Strangers",
+            "release_mbid": "76df3287-6cda-33eb-8e9a-044b5e15ffdd",
+            "release_name": "Dummy"
+        }"#;
+        let response = MetadataLookupResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(response.recording_mbid.as_deref(), Some(STRANGERS));
+        assert_eq!(response.artist_mbids, Some(vec![PORTISHEAD.to_string()]));
+        let metadata = response.metadata.unwrap();
+        assert_eq!(metadata.recording.name, "Strangers");
+        assert_eq!(metadata.release.unwrap().year, Some(1994));
+        assert_eq!(metadata.artist, None);
+
+        // Nothing was found
+        let response = MetadataLookupResponse::from_response(HttpResponse::new(200, "{}")).unwrap();
+        assert_eq!(response.recording_mbid, None);
+        assert_eq!(response.metadata, None);
+    }
+}
@@ -2,14 +2,15 @@
 //!
 //! Every response type has the `rate_limit` field, which contains rate limiting
 //! information. See the documentation of the [`RateLimit`] type for more
-//! details.
+//! details. It also has the `attempts` field, which contains the number of times
+//! the request was attempted, including retries (see [`RetryPolicy`](super::RetryPolicy)).
 
 #![allow(missing_docs)]
 
-use attohttpc::Response;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 
+pub(crate) use crate::raw::transport::HttpResponse;
 use crate::Error;
 
 // Sub modules
@@ -26,6 +27,7 @@ pub mod social;
 pub mod statistics;
 
 // Reexport of the sub modules
+#[allow(unused_imports)]
 pub use crate::raw::response::art::*;
 pub use crate::raw::response::core::*;
 pub use crate::raw::response::metadata::*;
@@ -33,7 +35,9 @@ pub use crate::raw::response::misc::*;
 pub use crate::raw::response::playlists::*;
 pub use crate::raw::response::popularity::*;
 pub use crate::raw::response::recommendations::*;
+#[allow(unused_imports)]
 pub use crate::raw::response::recordings::*;
+#[allow(unused_imports)]
 pub use crate::raw::response::settings::*;
 pub use crate::raw::response::social::*;
 pub use crate::raw::response::statistics::*;
@@ -56,33 +60,11 @@ pub struct RateLimit {
 impl RateLimit {
     /// Extract rate limiting information from the `X-RateLimit-` headers.
     /// Only returns `Some` if all fields are present and valid.
-    fn from_headers(response: &Response) -> Option<Self> {
-        let headers = response.headers();
-
-        let limit = headers
-            .get("X-RateLimit-Limit")?
-            .to_str()
-            .ok()?
-            .parse()
-            .ok()?;
-        let remaining = headers
-            .get("X-RateLimit-Remaining")?
-            .to_str()
-            .ok()?
-            .parse()
-            .ok()?;
-        let reset_in = headers
-            .get("X-RateLimit-Reset-In")?
-            .to_str()
-            .ok()?
-            .parse()
-            .ok()?;
-        let reset = headers
-            .get("X-RateLimit-Reset")?
-            .to_str()
-            .ok()?
-            .parse()
-            .ok()?;
+    pub(crate) fn from_headers(response: &HttpResponse) -> Option<Self> {
+        let limit = response.header("X-RateLimit-Limit")?.parse().ok()?;
+        let remaining = response.header("X-RateLimit-Remaining")?.parse().ok()?;
+        let reset_in = response.header("X-RateLimit-Reset-In")?.parse().ok()?;
+        let reset = response.header("X-RateLimit-Reset")?.parse().ok()?;
 
         Some(Self {
             limit,
@@ -94,15 +76,45 @@ impl RateLimit {
 }
 
 /// Internal trait for response types.
-/// Allows converting the response type from an `attohttpc::Response`,
+/// Allows converting the response type from a [`HttpResponse`],
 /// by deserializing the body into the response type and then
 /// adding the `rate_limit` field from headers.
 pub(crate) trait ResponseType: DeserializeOwned {
-    fn from_response(response: Response) -> Result<Self, Error>;
+    fn from_response(response: HttpResponse) -> Result<Self, Error>;
+
+    /// Set the number of attempts that were made to get the response.
+    fn set_attempts(&mut self, attempts: u32);
+}
+
+/// A response of the API, with the number of attempts that were made to get it.
+pub(crate) struct Attempted {
+    pub(crate) response: HttpResponse,
+    pub(crate) attempts: u32,
+}
+
+impl Attempted {
+    /// Convert the response into a response type, reporting the number of attempts
+    /// in the response type, or in the error if the API returned an error.
+    pub(crate) fn into_response<R: ResponseType>(self) -> Result<R, Error> {
+        let attempts = self.attempts;
+        let mut result = R::from_response(self.response).map_err(|e| e.with_attempts(attempts))?;
+        result.set_attempts(attempts);
+        Ok(result)
+    }
+
+    /// Like [`Attempted::into_response`], but maps `204 No Content` to `None`.
+    /// The API returns 204 and an empty document for example if there are no statistics.
+    pub(crate) fn into_optional_response<R: ResponseType>(self) -> Result<Option<R>, Error> {
+        if self.response.status == 204 {
+            Ok(None)
+        } else {
+            self.into_response().map(Some)
+        }
+    }
 }
 
 /// Internal macro for response types.
-/// Wraps the definition of a response type, adds the `rate_limit` field,
+/// Wraps the definition of a response type, adds the `rate_limit` and `attempts` fields,
 /// and implements the `ResponseType` trait.
 macro_rules! response_type {
     (
@@ -116,20 +128,114 @@ macro_rules! response_type {
         pub struct $name {
             #[serde(skip)]
             pub rate_limit: Option<crate::raw::response::RateLimit>,
+            #[serde(skip)]
+            pub attempts: u32,
             $(pub $field: $field_ty),*
         }
 
         impl crate::raw::response::ResponseType for $name {
-            fn from_response(response: crate::raw::response::Response) -> Result<Self, crate::raw::response::Error> {
+            fn from_response(response: crate::raw::response::HttpResponse) -> Result<Self, crate::raw::response::Error> {
                 let response = crate::raw::response::Error::try_from_error_response(response)?;
                 let rate_limit = crate::raw::response::RateLimit::from_headers(&response);
                 let mut result: Self = response.json()?;
                 result.rate_limit = rate_limit;
+                result.attempts = 1;
                 Ok(result)
             }
+
+            fn set_attempts(&mut self, attempts: u32) {
+                self.attempts = attempts;
+            }
+        }
+    }
+}
+
+/// Internal macro for response types whose body is not an object, such as a list of items.
+/// Like `response_type!`, but the response type has a single field besides `rate_limit`
+/// and `attempts`, which is deserialized from the whole body.
+macro_rules! body_response_type {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            pub $field:ident: $field_ty:ty
+            $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            #[serde(skip)]
+            pub rate_limit: Option<crate::raw::response::RateLimit>,
+            #[serde(skip)]
+            pub attempts: u32,
+            pub $field: $field_ty,
+        }
+
+        impl crate::raw::response::ResponseType for $name {
+            fn from_response(response: crate::raw::response::HttpResponse) -> Result<Self, crate::raw::response::Error> {
+                let response = crate::raw::response::Error::try_from_error_response(response)?;
+                Ok(Self {
+                    rate_limit: crate::raw::response::RateLimit::from_headers(&response),
+                    attempts: 1,
+                    $field: response.json()?,
+                })
+            }
+
+            fn set_attempts(&mut self, attempts: u32) {
+                self.attempts = attempts;
+            }
         }
     }
 }
 
-// Let the childrens access the macro
+// Let the childrens access the macros
+pub(super) use body_response_type;
 pub(super) use response_type;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limited(response: HttpResponse) -> HttpResponse {
+        response
+            .with_header("X-RateLimit-Limit", "30")
+            .with_header("X-RateLimit-Remaining", "29")
+            .with_header("X-RateLimit-Reset-In", "10")
+            .with_header("X-RateLimit-Reset", "1700000000")
+    }
+
+    #[test]
+    fn body_response_types_deserialize_the_whole_body() {
+        let body = r#"[{"artist_mbid": "a", "total_listen_count": 3, "total_user_count": null}]"#;
+        let response =
+            PopularityArtistResponse::from_response(rate_limited(HttpResponse::new(200, body)))
+                .unwrap();
+
+        assert_eq!(response.rate_limit.unwrap().remaining, 29);
+        assert_eq!(
+            response.artists,
+            [PopularityArtist {
+                artist_mbid: "a".to_string(),
+                total_listen_count: Some(3),
+                total_user_count: None,
+            }]
+        );
+
+        let response = PlaylistExportResponse::from_response(HttpResponse::new(
+            200,
+            r#""https://open.spotify.com/playlist/id""#,
+        ))
+        .unwrap();
+        assert_eq!(
+            response.external_url,
+            "https://open.spotify.com/playlist/id"
+        );
+        assert!(response.rate_limit.is_none());
+    }
+
+    #[test]
+    fn body_response_types_report_api_errors() {
+        let body = r#"{"code": 400, "error": "invalid MBID"}"#;
+        let error = PopularityArtistResponse::from_response(HttpResponse::new(400, body));
+        assert!(matches!(error, Err(Error::Api { code: 400, .. })));
+    }
+}
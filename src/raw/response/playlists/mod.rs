@@ -2,7 +2,10 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::raw::jspf;
-use crate::raw::response::response_type;
+use crate::raw::response::{
+    body_response_type, response_type, HttpResponse, RateLimit, ResponseType,
+};
+use crate::Error;
 
 // --------- GET /1/user/(playlist_user_name)/playlists
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-user-(playlist_user_name)-playlists
@@ -61,7 +64,16 @@ response_type! {
 // --------- GET /1/playlist/search
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-search
 
-// TODO
+response_type! {
+    /// Response type for [`Client::playlist_search`](super::Client::playlist_search).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistSearchResponse {
+        pub count: u64,
+        pub offset: u64,
+        pub playlist_count: u64,
+        pub playlists: Vec<jspf::Playlist>,
+    }
+}
 
 // --------- GET /1/playlist/(playlist_mbid)
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid)
@@ -77,7 +89,45 @@ response_type! {
 // --------- GET /1/playlist/(playlist_mbid)/xspf
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-(playlist_mbid)-xspf
 
-// TODO
+/// Response type for [`Client::get_playlist_xspf`](super::Client::get_playlist_xspf).
+///
+/// Unlike other responses, this is not JSON: the body is an XSPF (XML) document.
+/// With the `xspf` feature, it can be converted into a [`jspf::Playlist`]
+/// with `GetPlaylistXspfResponse::playlist`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetPlaylistXspfResponse {
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimit>,
+    #[serde(skip)]
+    pub attempts: u32,
+    pub xspf: String,
+}
+
+impl ResponseType for GetPlaylistXspfResponse {
+    fn from_response(response: HttpResponse) -> Result<Self, Error> {
+        let response = Error::try_from_error_response(response)?;
+        Ok(Self {
+            rate_limit: RateLimit::from_headers(&response),
+            attempts: 1,
+            xspf: String::from_utf8_lossy(&response.body).into_owned(),
+        })
+    }
+
+    fn set_attempts(&mut self, attempts: u32) {
+        self.attempts = attempts;
+    }
+}
+
+// --------- POST /1/playlist/edit/(playlist_mbid)
+// https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-edit-(playlist_mbid)
+
+response_type! {
+    /// Response type for [`Client::playlist_edit`](super::Client::playlist_edit).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistEditResponse {
+        pub status: String,
+    }
+}
 
 // --------- POST /1/playlist/(playlist_mbid)/item/add
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-item-add
@@ -85,17 +135,35 @@ response_type! {
 // --------- POST /1/playlist/(playlist_mbid)/item/add/(int: offset)
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-item-add-(int-offset)
 
-// TODO
+response_type! {
+    /// Response type for [`Client::playlist_item_add`](super::Client::playlist_item_add).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistItemAddResponse {
+        pub status: String,
+    }
+}
 
 // --------- POST /1/playlist/(playlist_mbid)/item/move
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-item-move
 
-// TODO
+response_type! {
+    /// Response type for [`Client::playlist_item_move`](super::Client::playlist_item_move).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistItemMoveResponse {
+        pub status: String,
+    }
+}
 
 // --------- POST /1/playlist/(playlist_mbid)/item/delete
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-item-delete
 
-// TODO
+response_type! {
+    /// Response type for [`Client::playlist_item_delete`](super::Client::playlist_item_delete).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistItemDeleteResponse {
+        pub status: String,
+    }
+}
 
 // --------- POST /1/playlist/(playlist_mbid)/delete
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-delete
@@ -123,24 +191,83 @@ response_type! {
 // --------- POST /1/playlist/(playlist_mbid)/export/(service)
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-(playlist_mbid)-export-(service)
 
-// TODO
+body_response_type! {
+    /// Response type for [`Client::playlist_export`](super::Client::playlist_export)
+    /// and [`Client::playlist_export_jspf`](super::Client::playlist_export_jspf).
+    ///
+    /// The API responds with just the URL of the exported playlist on the service.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistExportResponse {
+        pub external_url: String,
+    }
+}
 
 // --------- GET /1/playlist/import/(service)
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-import-(service)
 
-// TODO
+body_response_type! {
+    /// Response type for [`Client::playlist_import`](super::Client::playlist_import).
+    ///
+    /// The API responds with the list of playlists of the user on the service.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistImportResponse {
+        pub playlists: Vec<ServicePlaylist>,
+    }
+}
 
-// --------- GET /1/playlist/spotify/(playlist_id)/tracks
-// https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-spotify-(playlist_id)-tracks
+/// Type of the [`PlaylistImportResponse::playlists`] field.
+///
+/// The API passes on the playlist objects of the service as they are,
+/// so apart from the ID, their fields depend on the service.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ServicePlaylist {
+    /// The ID of the playlist on the service, to be passed to
+    /// [`Client::playlist_service_tracks`](super::Client::playlist_service_tracks).
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: String,
+
+    /// The other fields of the playlist, as returned by the service.
+    #[serde(flatten)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
 
-// TODO
+impl ServicePlaylist {
+    /// Get the name of the playlist, which is found in a different place for every service.
+    pub fn name(&self) -> Option<&str> {
+        self.data
+            .get("name")
+            .or_else(|| self.data.get("title"))
+            .or_else(|| self.data.get("attributes")?.get("name"))
+            .and_then(|name| name.as_str())
+    }
+}
 
+/// Deserialize an ID that is a string for some services and a number for others.
+fn string_or_number<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(id) => Ok(id),
+        serde_json::Value::Number(id) => Ok(id.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or number, found {other}"
+        ))),
+    }
+}
+
+// --------- GET /1/playlist/spotify/(playlist_id)/tracks
+// https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-spotify-(playlist_id)-tracks
+// Or
 // ---------  GET /1/playlist/apple_music/(playlist_id)/tracks
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#get--1-playlist-apple_music-(playlist_id)-tracks
 
-// TODO
+response_type! {
+    /// Response type for [`Client::playlist_service_tracks`](super::Client::playlist_service_tracks).
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PlaylistServiceTracksResponse {
+        pub playlist: jspf::PlaylistInfo,
+    }
+}
 
 // --------- POST /1/playlist/export-jspf/(service)
 // https://listenbrainz.readthedocs.io/en/latest/users/api/playlist.html#post--1-playlist-export-jspf-(service)
 
-// TODO
+// The response is the same as for POST /1/playlist/(playlist_mbid)/export/(service): PlaylistExportResponse
@@ -1,3 +1,226 @@
-//TODO: https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html
-// Currently no api responses have been made for this page.
-// The header comments will be made as needed
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::raw::response::body_response_type;
+use crate::raw::response::metadata::MetadataReleaseGroup;
+
+// --------- GET /1/popularity/top-recordings-for-artist/(artist_mbid)
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-recordings-for-artist-(artist_mbid)
+
+body_response_type! {
+    /// Response type for [`Client::popularity_top_recordings_for_artist`](super::Client::popularity_top_recordings_for_artist).
+    ///
+    /// The API responds with the list of recordings, most listened first.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityTopRecordingsForArtistResponse {
+        pub recordings: Vec<PopularityTopRecording>,
+    }
+}
+
+/// Type of the [`PopularityTopRecordingsForArtistResponse::recordings`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityTopRecording {
+    pub recording_mbid: String,
+    pub recording_name: String,
+    pub artist_name: String,
+    #[serde(default)]
+    pub artist_mbids: Vec<String>,
+    pub release_mbid: Option<String>,
+    pub release_name: Option<String>,
+    /// Length of the recording in milliseconds.
+    pub length: Option<u64>,
+    pub caa_id: Option<i64>,
+    pub caa_release_mbid: Option<String>,
+    pub total_listen_count: u64,
+    pub total_user_count: u64,
+}
+
+// --------- GET /1/popularity/top-release-groups-for-artist/(artist_mbid)
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#get--1-popularity-top-release-groups-for-artist-(artist_mbid)
+
+body_response_type! {
+    /// Response type for [`Client::popularity_top_release_groups_for_artist`](super::Client::popularity_top_release_groups_for_artist).
+    ///
+    /// The API responds with the list of release groups, most listened first.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityTopReleaseGroupsForArtistResponse {
+        pub release_groups: Vec<PopularityTopReleaseGroup>,
+    }
+}
+
+/// Type of the [`PopularityTopReleaseGroupsForArtistResponse::release_groups`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityTopReleaseGroup {
+    pub release_group_mbid: String,
+    pub release_group: MetadataReleaseGroup,
+    pub artist: Option<PopularityArtistCredit>,
+    pub total_listen_count: u64,
+    pub total_user_count: u64,
+}
+
+/// Type of the [`PopularityTopReleaseGroup::artist`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityArtistCredit {
+    pub artist_credit_name: String,
+    #[serde(default)]
+    pub artist_mbids: Vec<String>,
+}
+
+// --------- POST /1/popularity/recording
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-recording
+
+body_response_type! {
+    /// Response type for [`Client::popularity_recording`](super::Client::popularity_recording).
+    ///
+    /// The API responds with the popularity of every requested recording, in order.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityRecordingResponse {
+        pub recordings: Vec<PopularityRecording>,
+    }
+}
+
+/// Type of the [`PopularityRecordingResponse::recordings`] field.
+///
+/// The counts are [`None`] if nobody has listened to the recording.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityRecording {
+    pub recording_mbid: String,
+    pub total_listen_count: Option<u64>,
+    pub total_user_count: Option<u64>,
+}
+
+// --------- POST /1/popularity/artist
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-artist
+
+body_response_type! {
+    /// Response type for [`Client::popularity_artist`](super::Client::popularity_artist).
+    ///
+    /// The API responds with the popularity of every requested artist, in order.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityArtistResponse {
+        pub artists: Vec<PopularityArtist>,
+    }
+}
+
+/// Type of the [`PopularityArtistResponse::artists`] field.
+///
+/// The counts are [`None`] if nobody has listened to the artist.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityArtist {
+    pub artist_mbid: String,
+    pub total_listen_count: Option<u64>,
+    pub total_user_count: Option<u64>,
+}
+
+// --------- POST /1/popularity/release
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release
+
+body_response_type! {
+    /// Response type for [`Client::popularity_release`](super::Client::popularity_release).
+    ///
+    /// The API responds with the popularity of every requested release, in order.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityReleaseResponse {
+        pub releases: Vec<PopularityRelease>,
+    }
+}
+
+/// Type of the [`PopularityReleaseResponse::releases`] field.
+///
+/// The counts are [`None`] if nobody has listened to the release.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityRelease {
+    pub release_mbid: String,
+    pub total_listen_count: Option<u64>,
+    pub total_user_count: Option<u64>,
+}
+
+// --------- POST /1/popularity/release-group
+// https://listenbrainz.readthedocs.io/en/latest/users/api/popularity.html#post--1-popularity-release-group
+
+body_response_type! {
+    /// Response type for [`Client::popularity_release_group`](super::Client::popularity_release_group).
+    ///
+    /// The API responds with the popularity of every requested release group, in order.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct PopularityReleaseGroupResponse {
+        pub release_groups: Vec<PopularityReleaseGroup>,
+    }
+}
+
+/// Type of the [`PopularityReleaseGroupResponse::release_groups`] field.
+///
+/// The counts are [`None`] if nobody has listened to the release group.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PopularityReleaseGroup {
+    pub release_group_mbid: String,
+    pub total_listen_count: Option<u64>,
+    pub total_user_count: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::response::{HttpResponse, ResponseType};
+
+    const KNOWN: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+    const UNKNOWN: &str = "00000000-0000-0000-0000-000000000000";
+
+    #[test]
+    fn parses_null_counts_of_unknown_mbids() {
+        let body = format!(
+            r#"[
+                {{"recording_mbid": "{KNOWN}", "total_listen_count": 1200, "total_user_count": 310}},
+                {{"recording_mbid": "{UNKNOWN}", "total_listen_count": null, "total_user_count": null}}
+            ]"#
+        );
+        let response =
+            PopularityRecordingResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(
+            response.recordings,
+            [
+                PopularityRecording {
+                    recording_mbid: KNOWN.to_string(),
+                    total_listen_count: Some(1200),
+                    total_user_count: Some(310),
+                },
+                PopularityRecording {
+                    recording_mbid: UNKNOWN.to_string(),
+                    total_listen_count: None,
+                    total_user_count: None,
+                },
+            ]
+        );
+
+        let body = format!(
+            r#"[{{"release_mbid": "{UNKNOWN}", "total_listen_count": null, "total_user_count": null}}]"#
+        );
+        let response =
+            PopularityReleaseResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(response.releases[0].total_listen_count, None);
+        assert_eq!(response.releases[0].total_user_count, None);
+
+        let body = format!(
+            r#"[{{"release_group_mbid": "{UNKNOWN}", "total_listen_count": null, "total_user_count": null}}]"#
+        );
+        let response =
+            PopularityReleaseGroupResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(response.release_groups[0].release_group_mbid, UNKNOWN);
+        assert_eq!(response.release_groups[0].total_listen_count, None);
+    }
+
+    #[test]
+    fn parses_missing_counts_as_none() {
+        let body = format!(r#"[{{"artist_mbid": "{UNKNOWN}"}}]"#);
+        let response =
+            PopularityArtistResponse::from_response(HttpResponse::new(200, body)).unwrap();
+        assert_eq!(
+            response.artists,
+            [PopularityArtist {
+                artist_mbid: UNKNOWN.to_string(),
+                total_listen_count: None,
+                total_user_count: None,
+            }]
+        );
+    }
+}
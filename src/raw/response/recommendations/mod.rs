@@ -1,3 +1,200 @@
-//TODO: https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html
-// Currently no api responses have been made for this page.
-// The header comments will be made as needed
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::raw::request::RecommendationFeedbackRating;
+use crate::raw::response::response_type;
+
+// --------- GET /1/cf/recommendation/user/(user_name)/recording
+// https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-cf-recommendation-user-(user_name)-recording
+
+response_type! {
+    /// Response type for [`Client::cf_recommendation_user_recording`](super::Client::cf_recommendation_user_recording).
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+    pub struct CfRecommendationUserRecordingResponse {
+        pub payload: CfRecommendationUserRecordingPayload,
+    }
+}
+
+/// Type of the [`CfRecommendationUserRecordingResponse::payload`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CfRecommendationUserRecordingPayload {
+    pub user_name: String,
+    /// The recommended recordings, best first.
+    pub mbids: Vec<CfRecommendationUserRecordingMbid>,
+    pub count: u64,
+    pub offset: u64,
+    pub total_mbid_count: u64,
+    /// When the recommendations were generated, as a UNIX timestamp.
+    pub last_updated: i64,
+    /// The kind of recommendations, `"cf"` (collaborative filtering).
+    #[serde(rename = "type")]
+    pub recommendation_type: String,
+    /// The kind of the recommended entities, `"recording"`.
+    pub entity: String,
+}
+
+/// Type of the [`CfRecommendationUserRecordingPayload::mbids`] field.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CfRecommendationUserRecordingMbid {
+    pub recording_mbid: String,
+    /// How well the recording matches the taste of the user.
+    pub score: f64,
+}
+
+// --------- POST /1/recommendation/feedback/submit
+// https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-submit
+
+response_type! {
+    /// Response type for [`Client::recommendation_feedback_submit`](super::Client::recommendation_feedback_submit).
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct RecommendationFeedbackSubmitResponse {
+        pub status: String,
+    }
+}
+
+// --------- POST /1/recommendation/feedback/delete
+// https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#post--1-recommendation-feedback-delete
+
+response_type! {
+    /// Response type for [`Client::recommendation_feedback_delete`](super::Client::recommendation_feedback_delete).
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct RecommendationFeedbackDeleteResponse {
+        pub status: String,
+    }
+}
+
+// --------- GET /1/recommendation/feedback/user/(user_name)
+// https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name)
+
+response_type! {
+    /// Response type for [`Client::recommendation_feedback_user`](super::Client::recommendation_feedback_user).
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct RecommendationFeedbackUserResponse {
+        pub user_name: String,
+        pub feedback: Vec<RecommendationFeedback>,
+        pub count: u64,
+        pub offset: u64,
+        pub total_count: u64,
+    }
+}
+
+/// Feedback of a user on a recommended recording.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RecommendationFeedback {
+    pub recording_mbid: String,
+    pub rating: RecommendationFeedbackRating,
+    /// When the feedback was submitted, as a UNIX timestamp.
+    pub created: i64,
+}
+
+// --------- GET /1/recommendation/feedback/user/(user_name)/recordings
+// https://listenbrainz.readthedocs.io/en/latest/users/api/recommendation.html#get--1-recommendation-feedback-user-(user_name)-recordings
+
+response_type! {
+    /// Response type for [`Client::recommendation_feedback_user_recordings`](super::Client::recommendation_feedback_user_recordings).
+    ///
+    /// Recordings that the user has not rated are left out.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+    pub struct RecommendationFeedbackUserRecordingsResponse {
+        pub user_name: String,
+        pub feedback: Vec<RecommendationFeedback>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::response::{HttpResponse, ResponseType};
+
+    const STRANGERS: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+    const ROADS: &str = "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3";
+
+    #[test]
+    fn parses_feedback_of_users() {
+        let body = format!(
+            r#"{{
+                "count": 2,
+                "feedback": [
+                    {{"created": 1700000100, "rating": "bad_recommendation", "recording_mbid": "{STRANGERS}"}},
+                    {{"created": 1700000000, "rating": "love", "recording_mbid": "{ROADS}"}}
+                ],
+                "offset": 0,
+                "total_count": 5,
+                "user_name": "koen"
+            }}"#
+        );
+        let response =
+            RecommendationFeedbackUserResponse::from_response(HttpResponse::new(200, body))
+                .unwrap();
+
+        assert_eq!(response.user_name, "koen");
+        assert_eq!((response.count, response.offset), (2, 0));
+        assert_eq!(response.total_count, 5);
+        assert_eq!(
+            response.feedback,
+            [
+                RecommendationFeedback {
+                    recording_mbid: STRANGERS.to_string(),
+                    rating: RecommendationFeedbackRating::BadRecommendation,
+                    created: 1700000100,
+                },
+                RecommendationFeedback {
+                    recording_mbid: ROADS.to_string(),
+                    rating: RecommendationFeedbackRating::Love,
+                    created: 1700000000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_feedback_of_users_for_recordings() {
+        let body = format!(
+            r#"{{
+                "feedback": [{{"created": 1700000000, "rating": "dislike", "recording_mbid": "{ROADS}"}}],
+                "user_name": "koen"
+            }}"#
+        );
+        let response = RecommendationFeedbackUserRecordingsResponse::from_response(
+            HttpResponse::new(200, body),
+        )
+        .unwrap();
+
+        assert_eq!(response.user_name, "koen");
+        assert_eq!(response.feedback.len(), 1);
+        assert_eq!(
+            response.feedback[0].rating,
+            RecommendationFeedbackRating::Dislike
+        );
+    }
+
+    #[test]
+    fn parses_submit_and_delete_statuses() {
+        let response = RecommendationFeedbackSubmitResponse::from_response(HttpResponse::new(
+            200,
+            r#"{"status": "ok"}"#,
+        ))
+        .unwrap();
+        assert_eq!(response.status, "ok");
+
+        let response = RecommendationFeedbackDeleteResponse::from_response(HttpResponse::new(
+            200,
+            r#"{"status": "ok"}"#,
+        ))
+        .unwrap();
+        assert_eq!(response.status, "ok");
+    }
+
+    #[test]
+    fn rejects_unknown_ratings() {
+        let body = format!(
+            r#"{{"feedback": [{{"created": 0, "rating": "meh", "recording_mbid": "{ROADS}"}}], "user_name": "koen"}}"#
+        );
+        assert!(matches!(
+            RecommendationFeedbackUserRecordingsResponse::from_response(HttpResponse::new(
+                200, body
+            )),
+            Err(crate::Error::Json(_))
+        ));
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::raw::request::StatsRange;
 use crate::raw::response::response_type;
 
 // --------- GET /1/stats/user/(user_name)/artists
@@ -26,7 +27,7 @@ pub struct StatsUserArtistsPayload {
     pub from_ts: i64,
     pub to_ts: i64,
     pub last_updated: i64,
-    pub range: String,
+    pub range: StatsRange,
 }
 
 /// Type of the [`StatsUserArtistsPayload::artists`] field.
@@ -59,7 +60,7 @@ pub struct StatsUserReleasesPayload {
     pub from_ts: i64,
     pub to_ts: i64,
     pub last_updated: i64,
-    pub range: String,
+    pub range: StatsRange,
 }
 
 /// Type of the [`StatsUserReleasesPayload::releases`] field.
@@ -95,7 +96,7 @@ pub struct StatsUserRecordingsPayload {
     pub from_ts: i64,
     pub to_ts: i64,
     pub last_updated: i64,
-    pub range: String,
+    pub range: StatsRange,
 }
 
 /// Type of the [`StatsUserRecordingsPayload::recordings`] field.
@@ -162,7 +163,7 @@ pub struct StatsUserDailyActivityPayload {
     pub from_ts: i64,
     pub to_ts: i64,
     pub last_updated: i64,
-    pub stats_range: String,
+    pub stats_range: StatsRange,
 }
 
 /// Type of the [`StatsUserDailyActivityPayload::daily_activity`] field.
@@ -197,7 +198,7 @@ pub struct StatsUserArtistMapPayload {
     pub from_ts: i64,
     pub to_ts: i64,
     pub last_updated: i64,
-    pub range: String,
+    pub range: StatsRange,
 }
 
 /// Type of the [`StatsUserArtistMapPayload::artist_map`] field.
@@ -230,7 +231,7 @@ pub struct StatsReleaseGroupListenersPayload {
     pub listeners: Vec<StatsReleaseGroupListenersListeners>,
     pub release_group_mbid: String,
     pub release_group_name: String,
-    pub stats_range: String,
+    pub stats_range: StatsRange,
     pub to_ts: i64,
     pub total_listen_count: i64,
 }
@@ -258,7 +259,7 @@ pub struct StatsSitewideArtistsPayload {
     pub artists: Vec<StatsSitewideArtistsArtist>,
     pub offset: u64,
     pub count: u64,
-    pub range: String,
+    pub range: StatsRange,
     pub last_updated: i64,
     pub from_ts: i64,
     pub to_ts: i64,
@@ -0,0 +1,206 @@
+use std::collections::hash_map::RandomState;
+use std::error::Error as StdError;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::time::Duration;
+
+use super::transport::HttpResponse;
+use crate::Error;
+
+/// Controls if and how [`Client`](super::Client) retries requests that failed
+/// because of transient problems, such as a `502 Bad Gateway` response or a
+/// connection that could not be established.
+///
+/// Between attempts, the client waits for an exponentially increasing amount of time:
+/// [`initial_backoff`](Self::initial_backoff) after the first attempt, twice that after
+/// the second, and so on, up to [`max_backoff`](Self::max_backoff).
+///
+/// Requests that are not idempotent, i.e. `POST` requests like
+/// [`Client::submit_listens`](super::Client::submit_listens) or
+/// [`Client::playlist_create`](super::Client::playlist_create), are only retried when the
+/// API certainly did not process them: when the connection could not be established,
+/// or when the API answered with `429 Too Many Requests` (if that status is retryable).
+///
+/// The number of attempts made for a request is reported in the `attempts` field of its
+/// response, or of [`Error::Api`] if the API answered every attempt with an error.
+///
+/// The default policy does not retry at all.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use listenbrainz::raw::{Client, RetryPolicy};
+/// let mut client = Client::new();
+/// client.set_retry_policy(RetryPolicy {
+///     max_attempts: 5,
+///     initial_backoff: Duration::from_secs(1),
+///     ..RetryPolicy::default()
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of times a request is attempted, including the first attempt.
+    /// A value of 0 or 1 disables retries.
+    pub max_attempts: u32,
+
+    /// Time to wait before the first retry.
+    pub initial_backoff: Duration,
+
+    /// Upper bound on the time to wait between attempts.
+    pub max_backoff: Duration,
+
+    /// If `true`, a random part of the backoff is left out, so that many clients
+    /// failing at the same time don't retry at the same moment.
+    pub jitter: bool,
+
+    /// HTTP status codes that are considered transient.
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl RetryPolicy {
+    /// Construct a policy that attempts requests up to `max_attempts` times,
+    /// with the default backoff and retryable statuses.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Check if a request that resulted in `result` should be attempted again.
+    pub(crate) fn should_retry(
+        &self,
+        result: &Result<HttpResponse, Error>,
+        idempotent: bool,
+    ) -> bool {
+        match result {
+            Ok(response) => {
+                self.retryable_statuses.contains(&response.status)
+                    && (idempotent || response.status == 429)
+            }
+            Err(Error::Http(error)) => idempotent || is_connect_error(error.as_ref()),
+            Err(_) => false,
+        }
+    }
+
+    /// Time to wait after the given (1-based) attempt failed.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            // Wait at least half of the backoff, and a random part of the other half
+            let half = backoff / 2;
+            let random = RandomState::new().build_hasher().finish();
+            half + half.mul_f64(random as f64 / u64::MAX as f64)
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![429, 500, 502, 503, 504],
+        }
+    }
+}
+
+/// Check if the error means that the connection could not be established,
+/// in which case the request certainly did not reach the API.
+fn is_connect_error(error: &(dyn StdError + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            return matches!(
+                error.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::NotConnected
+            );
+        }
+        current = error.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            jitter: false,
+            ..RetryPolicy::default()
+        }
+    }
+
+    fn status(status: u16) -> Result<HttpResponse, Error> {
+        Ok(HttpResponse::new(status, ""))
+    }
+
+    fn io_error(kind: io::ErrorKind) -> Result<HttpResponse, Error> {
+        Err(Error::Http(Box::new(io::Error::new(kind, "test"))))
+    }
+
+    #[test]
+    fn retries_transient_statuses_of_idempotent_requests() {
+        let policy = policy();
+        assert!(policy.should_retry(&status(502), true));
+        assert!(policy.should_retry(&status(429), true));
+        assert!(!policy.should_retry(&status(200), true));
+        assert!(!policy.should_retry(&status(404), true));
+        assert!(policy.should_retry(&io_error(io::ErrorKind::TimedOut), true));
+    }
+
+    #[test]
+    fn retries_non_idempotent_requests_only_when_not_processed() {
+        let policy = policy();
+        assert!(!policy.should_retry(&status(502), false));
+        assert!(policy.should_retry(&status(429), false));
+        assert!(policy.should_retry(&io_error(io::ErrorKind::ConnectionRefused), false));
+        assert!(!policy.should_retry(&io_error(io::ErrorKind::TimedOut), false));
+        assert!(!policy.should_retry(&io_error(io::ErrorKind::ConnectionReset), false));
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let json = serde_json::from_str::<()>("not json").unwrap_err();
+        assert!(!policy().should_retry(&Err(Error::Json(json)), true));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_backoff() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn backoff_with_jitter_stays_within_bounds() {
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy()
+        };
+        for attempt in 1..10 {
+            let backoff = policy.backoff(attempt);
+            assert!(backoff <= policy.max_backoff);
+            assert!(backoff >= policy.initial_backoff / 2);
+        }
+    }
+}
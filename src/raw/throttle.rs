@@ -0,0 +1,128 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::response::RateLimit;
+use super::transport::HttpResponse;
+
+/// How long to wait after a `429 Too Many Requests` response that does not
+/// tell when the rate limit resets.
+const DEFAULT_RATE_LIMITED_WAIT: Duration = Duration::from_secs(1);
+
+/// Keeps track of the last rate limit reported by the API, and makes requests
+/// wait until the rate limit resets if they would exceed it.
+#[derive(Debug, Default)]
+pub(crate) struct Throttle {
+    state: Mutex<Option<ThrottleState>>,
+}
+
+#[derive(Debug)]
+struct ThrottleState {
+    /// Number of requests that can still be made before `reset_at`.
+    remaining: u64,
+
+    /// Moment at which the rate limit resets.
+    reset_at: Instant,
+}
+
+impl Throttle {
+    /// Block until a request can be made without exceeding the rate limit,
+    /// and count that request against the remaining budget.
+    pub(crate) fn wait(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+
+            match state.as_mut() {
+                Some(current) if current.reset_at <= now => {
+                    // The window has passed; the next response tells us the new budget.
+                    *state = None;
+                    None
+                }
+                Some(current) if current.remaining == 0 => Some(current.reset_at - now),
+                Some(current) => {
+                    current.remaining -= 1;
+                    None
+                }
+                None => None,
+            }
+        };
+
+        if let Some(wait) = wait {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Record the rate limit information of a response.
+    pub(crate) fn update(&self, response: &HttpResponse) {
+        let rate_limited = response.status == 429;
+
+        let mut new_state = match RateLimit::from_headers(response) {
+            Some(rate_limit) => ThrottleState {
+                remaining: rate_limit.remaining,
+                reset_at: Instant::now() + Duration::from_secs(rate_limit.reset_in),
+            },
+            // Without rate limit headers, honour `Retry-After` if present
+            None if rate_limited => ThrottleState {
+                remaining: 0,
+                reset_at: Instant::now()
+                    + response
+                        .header("Retry-After")
+                        .and_then(|value| value.parse().ok())
+                        .map(Duration::from_secs)
+                        .unwrap_or(DEFAULT_RATE_LIMITED_WAIT),
+            },
+            None => return,
+        };
+
+        if rate_limited {
+            new_state.remaining = 0;
+            new_state.reset_at = new_state
+                .reset_at
+                .max(Instant::now() + DEFAULT_RATE_LIMITED_WAIT);
+        }
+
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = Some(new_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_wait_without_rate_limit() {
+        let throttle = Throttle::default();
+        let start = Instant::now();
+        throttle.wait();
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn waits_after_too_many_requests() {
+        let throttle = Throttle::default();
+        throttle.update(&HttpResponse::new(429, "").with_header("Retry-After", "1"));
+
+        let start = Instant::now();
+        throttle.wait();
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[test]
+    fn waits_when_no_requests_remain() {
+        let throttle = Throttle::default();
+        throttle.update(
+            &HttpResponse::new(200, "")
+                .with_header("X-RateLimit-Limit", "30")
+                .with_header("X-RateLimit-Remaining", "1")
+                .with_header("X-RateLimit-Reset-In", "1")
+                .with_header("X-RateLimit-Reset", "0"),
+        );
+
+        let start = Instant::now();
+        throttle.wait();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        throttle.wait();
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+}
@@ -0,0 +1,642 @@
+//! Pluggable HTTP transport used by [`Client`](super::Client).
+//!
+//! [`Client`](super::Client) builds a transport-neutral [`HttpRequest`] for every API call
+//! and hands it to a [`Transport`], which performs the request and returns an [`HttpResponse`].
+//! By default this is [`AttohttpcTransport`], but any type implementing [`Transport`] can be
+//! used instead, for example to route requests through a custom HTTP client, or to return
+//! canned responses in tests without network access:
+//!
+//! ```
+//! use listenbrainz::raw::transport::{HttpRequest, HttpResponse, Transport};
+//! use listenbrainz::raw::{Client, UserName};
+//! use listenbrainz::Error;
+//!
+//! #[derive(Debug)]
+//! struct Canned;
+//!
+//! impl Transport for Canned {
+//!     fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+//!         assert_eq!(request.url, "http://localhost/1/user/koen/listen-count");
+//!         Ok(HttpResponse::new(200, r#"{"payload": {"count": 42}}"#))
+//!     }
+//! }
+//!
+//! let client = Client::new_with_transport("http://localhost/1/", Canned);
+//! let user = UserName::new("koen").unwrap();
+//! let response = client.user_listen_count(&user).unwrap();
+//! assert_eq!(response.payload.count, 42);
+//! ```
+//!
+//! With the `async` feature, `AsyncClient` uses an `AsyncTransport` in the same way,
+//! which is `ReqwestTransport` by default.
+
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+
+use attohttpc::header::{HeaderName, HeaderValue};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::Error;
+
+/// HTTP method of an [`HttpRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// `GET`
+    Get,
+    /// `POST`
+    Post,
+}
+
+/// A transport-neutral HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// The HTTP method.
+    pub method: Method,
+
+    /// The full URL of the request, without query parameters.
+    pub url: String,
+
+    /// The query parameters of the request.
+    pub params: Vec<(String, String)>,
+
+    /// The headers of the request.
+    pub headers: Vec<(String, String)>,
+
+    /// The body of the request, if any.
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Create a new request without parameters, headers or body.
+    pub fn new(method: Method, url: impl ToString) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            params: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Create a new `GET` request.
+    pub fn get(url: impl ToString) -> Self {
+        Self::new(Method::Get, url)
+    }
+
+    /// Create a new `POST` request.
+    pub fn post(url: impl ToString) -> Self {
+        Self::new(Method::Post, url)
+    }
+
+    /// Add a query parameter.
+    pub fn param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a header.
+    pub fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add the `Authorization` header for the given ListenBrainz token.
+    pub fn token(self, token: &str) -> Self {
+        self.header("Authorization", format!("Token {token}"))
+    }
+
+    /// Set the body to the JSON representation of `data`.
+    pub fn json<D: Serialize>(mut self, data: &D) -> Result<Self, Error> {
+        self.body = Some(serde_json::to_vec(data)?);
+        Ok(self.header("Content-Type", "application/json"))
+    }
+}
+
+/// A transport-neutral HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The HTTP status code.
+    pub status: u16,
+
+    /// The headers of the response.
+    pub headers: Vec<(String, String)>,
+
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Create a new response without headers.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a header.
+    pub fn with_header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Get the value of a header by its (case-insensitive) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Deserialize the body of the response from JSON.
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Performs HTTP requests on behalf of [`Client`](super::Client).
+///
+/// Implementations should return the response for any status code;
+/// turning error statuses into [`Error::Api`] is done by the client.
+/// Failures to perform the request at all should be reported as [`Error::Http`].
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Perform the request and return the response.
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error>;
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        (**self).send(request)
+    }
+}
+
+/// The `User-Agent` sent by [`AttohttpcTransport`] unless configured otherwise.
+pub const DEFAULT_USER_AGENT: &str = concat!("listenbrainz-rs/", env!("CARGO_PKG_VERSION"));
+
+/// The default [`Transport`], which performs requests using `attohttpc`.
+///
+/// Requests are made from a single [`attohttpc::Session`], so settings such as timeouts,
+/// the proxy and the `User-Agent` are shared by all requests. Use
+/// [`ClientBuilder`](super::ClientBuilder) to configure these settings, or
+/// [`AttohttpcTransport::with_session`] to use a custom session.
+///
+/// Note that `attohttpc` does not keep connections alive between requests; with the
+/// `keep-alive` feature, `ClientBuilder::keep_alive`
+/// uses a [`Transport`] that does.
+#[derive(Debug, Clone)]
+pub struct AttohttpcTransport {
+    session: attohttpc::Session,
+}
+
+impl AttohttpcTransport {
+    /// Construct a new transport with the default settings.
+    pub fn new() -> Self {
+        let mut session = attohttpc::Session::new();
+        session.header(attohttpc::header::USER_AGENT, DEFAULT_USER_AGENT);
+        Self::with_session(session)
+    }
+
+    /// Construct a new transport that makes its requests from the given session.
+    pub fn with_session(session: attohttpc::Session) -> Self {
+        Self { session }
+    }
+
+    /// Get the session this transport makes its requests from.
+    pub fn session(&self) -> &attohttpc::Session {
+        &self.session
+    }
+}
+
+impl Default for AttohttpcTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for AttohttpcTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let mut builder = match request.method {
+            Method::Get => self.session.get(&request.url),
+            Method::Post => self.session.post(&request.url),
+        };
+
+        builder = builder.params(&request.params);
+
+        for (name, value) in &request.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|error| Error::Http(Box::new(error)))?;
+            let value =
+                HeaderValue::from_str(value).map_err(|error| Error::Http(Box::new(error)))?;
+            builder = builder.try_header_append(name, value)?;
+        }
+
+        let response = match &request.body {
+            Some(body) => builder.bytes(body).send()?,
+            None => builder.send()?,
+        };
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.bytes()?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// A [`Transport`] that performs requests using the blocking client of `reqwest`,
+/// which keeps a pool of connections alive between requests.
+///
+/// Use it with [`ClientBuilder::keep_alive`](super::ClientBuilder::keep_alive), or construct
+/// one from a custom client with [`ReqwestBlockingTransport::with_client`].
+/// Like [`reqwest::blocking::Client`], it must not be used from within an async runtime.
+///
+/// Requires the `keep-alive` feature.
+#[cfg(feature = "keep-alive")]
+#[derive(Debug, Clone)]
+pub struct ReqwestBlockingTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "keep-alive")]
+impl ReqwestBlockingTransport {
+    /// Construct a new transport with the default settings.
+    pub fn new() -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self::with_client(client)
+    }
+
+    /// Construct a new transport that makes its requests from the given client.
+    pub fn with_client(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the client this transport makes its requests from.
+    pub fn client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+}
+
+#[cfg(feature = "keep-alive")]
+impl Default for ReqwestBlockingTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "keep-alive")]
+impl Transport for ReqwestBlockingTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+        };
+
+        let mut builder = self
+            .client
+            .request(method, &request.url)
+            .query(&request.params);
+
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let response = builder.send()?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.bytes()?.to_vec();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// A boxed future returned by [`AsyncTransport::send`].
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Performs HTTP requests on behalf of [`AsyncClient`](super::AsyncClient).
+///
+/// This is the asynchronous counterpart of [`Transport`], with the same contract.
+///
+/// Requires the `async` feature.
+#[cfg(feature = "async")]
+pub trait AsyncTransport: fmt::Debug + Send + Sync {
+    /// Perform the request and return the response.
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, Error>>;
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + ?Sized> AsyncTransport for Box<T> {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        (**self).send(request)
+    }
+}
+
+/// The default [`AsyncTransport`], which performs requests using `reqwest`.
+///
+/// Requests are made from a single [`reqwest::Client`], which keeps a pool of connections
+/// alive between requests. Use [`ReqwestTransport::with_client`] to configure timeouts,
+/// a proxy or the `User-Agent`.
+///
+/// Requires the `async` feature.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    /// Construct a new transport with the default settings.
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self::with_client(client)
+    }
+
+    /// Construct a new transport that makes its requests from the given client.
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the client this transport makes its requests from.
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+#[cfg(feature = "async")]
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let method = match request.method {
+                Method::Get => reqwest::Method::GET,
+                Method::Post => reqwest::Method::POST,
+            };
+
+            let mut builder = self
+                .client
+                .request(method, &request.url)
+                .query(&request.params);
+
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = &request.body {
+                builder = builder.body(body.clone());
+            }
+
+            let response = builder.send().await?;
+
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            let body = response.bytes().await?.to_vec();
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use super::{HttpRequest, HttpResponse, Transport};
+    use crate::Error;
+
+    type Respond = dyn Fn(&HttpRequest) -> Result<HttpResponse, Error> + Send + Sync;
+
+    /// A [`Transport`] (and [`AsyncTransport`](super::AsyncTransport)) for tests, which records all requests and answers them with a closure.
+    #[derive(Clone)]
+    pub(crate) struct MockTransport {
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+        respond: Arc<Respond>,
+    }
+
+    impl MockTransport {
+        pub(crate) fn new(
+            respond: impl Fn(&HttpRequest) -> Result<HttpResponse, Error> + Send + Sync + 'static,
+        ) -> Self {
+            Self {
+                requests: Arc::default(),
+                respond: Arc::new(respond),
+            }
+        }
+
+        /// Get all requests sent so far.
+        pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl fmt::Debug for MockTransport {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("MockTransport").finish_non_exhaustive()
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+            self.requests.lock().unwrap().push(request.clone());
+            (self.respond)(request)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl super::AsyncTransport for MockTransport {
+        fn send<'a>(
+            &'a self,
+            request: &'a HttpRequest,
+        ) -> super::BoxFuture<'a, Result<HttpResponse, Error>> {
+            Box::pin(std::future::ready(Transport::send(self, request)))
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// A request received by a [`StubServer`].
+    #[derive(Debug, Clone)]
+    pub(crate) struct StubRequest {
+        /// The method and target of the request, e.g. `GET /1/user/koen/listen-count`.
+        pub(crate) line: String,
+        pub(crate) headers: Vec<(String, String)>,
+    }
+
+    impl StubRequest {
+        pub(crate) fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorded {
+        requests: Mutex<Vec<StubRequest>>,
+        connections: AtomicUsize,
+    }
+
+    /// A minimal HTTP/1.1 server on `127.0.0.1` for tests, which answers every request with
+    /// the same JSON response, keeps connections alive and records the requests.
+    pub(crate) struct StubServer {
+        url: String,
+        recorded: Arc<Recorded>,
+    }
+
+    impl StubServer {
+        pub(crate) fn start(status: u16, body: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/1/", listener.local_addr().unwrap());
+            let recorded = Arc::<Recorded>::default();
+
+            let server = recorded.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { break };
+                    server.connections.fetch_add(1, Ordering::Relaxed);
+                    let server = server.clone();
+                    thread::spawn(move || serve(stream, status, body, &server));
+                }
+            });
+
+            Self { url, recorded }
+        }
+
+        /// The API URL of the server.
+        pub(crate) fn url(&self) -> &str {
+            &self.url
+        }
+
+        /// Get the method and target of all requests received so far.
+        pub(crate) fn requests(&self) -> Vec<String> {
+            self.received()
+                .into_iter()
+                .map(|request| request.line)
+                .collect()
+        }
+
+        /// Get all requests received so far.
+        pub(crate) fn received(&self) -> Vec<StubRequest> {
+            self.recorded.requests.lock().unwrap().clone()
+        }
+
+        /// The number of connections accepted so far.
+        pub(crate) fn connections(&self) -> usize {
+            self.recorded.connections.load(Ordering::Relaxed)
+        }
+    }
+
+    /// Answer the requests on a connection until the client closes it.
+    fn serve(stream: TcpStream, status: u16, body: &str, recorded: &Recorded) {
+        let mut reader = BufReader::new(stream);
+
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                return;
+            }
+
+            let mut headers = Vec::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let Some((name, value)) = line.trim_end().split_once(':') else {
+                    break;
+                };
+                let value = value.trim().to_string();
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.parse().unwrap();
+                }
+                headers.push((name.to_string(), value));
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut parts = request_line.split_whitespace();
+            let line = format!(
+                "{} {}",
+                parts.next().unwrap_or_default(),
+                parts.next().unwrap_or_default()
+            );
+            recorded
+                .requests
+                .lock()
+                .unwrap()
+                .push(StubRequest { line, headers });
+
+            let response = format!(
+                "HTTP/1.1 {status} Stub\r\n\
+                 Content-Type: application/json\r\n\
+                 Content-Length: {}\r\n\
+                 X-RateLimit-Limit: 30\r\n\
+                 X-RateLimit-Remaining: 29\r\n\
+                 X-RateLimit-Reset-In: 10\r\n\
+                 X-RateLimit-Reset: 1700000000\r\n\
+                 \r\n\
+                 {body}",
+                body.len()
+            );
+            let stream = reader.get_mut();
+            if stream.write_all(response.as_bytes()).is_err() {
+                return;
+            }
+            stream.flush().unwrap();
+        }
+    }
+}
@@ -0,0 +1,833 @@
+//! Conversion between [JSPF](super::jspf) playlists and the [XSPF format] (XML).
+//!
+//! Requires the `xspf` feature.
+//!
+//! All fields of JSPF have an XSPF counterpart. The MusicBrainz extensions of playlists and
+//! tracks are written as XSPF `<extension>` elements with the same `application` URIs as in JSPF.
+//! Their fields are written as child elements named after the JSPF keys, with one element per
+//! item for lists (`<collaborator>` and `<artist_mbid>`). Other extensions, and fields of the
+//! MusicBrainz extensions that are not known to this library, are written as JSON text.
+//! Fields whose key is not a valid element name are written as `<field name="...">` elements.
+//! Text is kept as is, including leading and trailing whitespace, so that everything survives
+//! a round trip, except that an empty list of collaborators is read back as a missing one:
+//!
+//! ```
+//! # use listenbrainz::raw::{jspf, xspf};
+//! # let json = r#"{"playlist": {"title": "Mix", "creator": "koen", "date": "2024-01-01T00:00:00Z",
+//! #     "identifier": "https://listenbrainz.org/playlist/f316bb0e-8e26-44f8-a802-6d2a3688fc7d",
+//! #     "annotation": null, "track": [],
+//! #     "extension": {"https://musicbrainz.org/doc/jspf#playlist": {
+//! #         "creator": "koen", "public": true, "last_modified_at": "2024-01-01T00:00:00Z",
+//! #         "created_for": null, "copied_from": null, "additional_metadata": null}}}}"#;
+//! let playlist: jspf::Playlist = serde_json::from_str(json)?;
+//!
+//! let xml = xspf::to_string(&playlist)?;
+//! assert!(xml.contains("<title>Mix</title>"));
+//!
+//! let parsed = xspf::from_str(&xml)?;
+//! assert_eq!(parsed, playlist);
+//! assert_eq!(parsed.playlist.musicbrainz().unwrap().public, Some(true));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [XSPF format]: https://xspf.org/spec
+
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use serde_json::{Map, Value};
+
+use super::jspf::{
+    AdditionalMetadata, AlgorithmMetadata, Attribution, Link, Meta, MusicBrainzPlaylistExtension,
+    MusicBrainzTrackExtension, Playlist, PlaylistExtension, PlaylistInfo, Track, TrackExtension,
+};
+use super::response::GetPlaylistXspfResponse;
+use crate::Error;
+
+/// XML namespace of XSPF version 1.
+const XSPF_NAMESPACE: &str = "http://xspf.org/ns/0/";
+
+/// `application` of the MusicBrainz playlist extension.
+const PLAYLIST_EXTENSION: &str = "https://musicbrainz.org/doc/jspf#playlist";
+
+/// `application` of the MusicBrainz track extension.
+const TRACK_EXTENSION: &str = "https://musicbrainz.org/doc/jspf#track";
+
+/// Name of the elements for extension fields whose name is not a valid element name.
+const FIELD_ELEMENT: &str = "field";
+
+/// Write a playlist as an XSPF document.
+pub fn to_string(playlist: &Playlist) -> Result<String, Error> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer
+        .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .map_err(xml_error)?;
+    write_element(&mut writer, &playlist_element(&playlist.playlist))?;
+
+    String::from_utf8(writer.into_inner()).map_err(xml_error)
+}
+
+/// Read a playlist from an XSPF document.
+///
+/// Elements that are missing from the document are left out of the playlist, so that
+/// playlists that were not created by ListenBrainz can be read as well.
+pub fn from_str(xspf: &str) -> Result<Playlist, Error> {
+    let root = parse(xspf)?;
+    if root.name != "playlist" {
+        return Err(Error::Xspf(format!(
+            "expected a <playlist> root element, found <{}>",
+            root.name
+        )));
+    }
+
+    Ok(Playlist {
+        playlist: playlist_info(&root)?,
+    })
+}
+
+impl GetPlaylistXspfResponse {
+    /// Convert the XSPF document of this response into a JSPF playlist.
+    ///
+    /// Requires the `xspf` feature.
+    pub fn playlist(&self) -> Result<Playlist, Error> {
+        from_str(&self.xspf)
+    }
+}
+
+/// Minimal XML element tree, used as an intermediate representation in both directions.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn text_child(self, name: &str, text: impl ToString) -> Self {
+        let mut child = Element::new(name);
+        child.text = text.to_string();
+        self.child(child)
+    }
+
+    fn optional_child(self, name: &str, text: Option<impl ToString>) -> Self {
+        match text {
+            Some(text) => self.text_child(name, text),
+            None => self,
+        }
+    }
+
+    fn text_children<'a>(self, name: &str, texts: impl IntoIterator<Item = &'a String>) -> Self {
+        texts
+            .into_iter()
+            .fold(self, |element, text| element.text_child(name, text))
+    }
+
+    fn rel_children<'a>(
+        self,
+        name: &str,
+        pairs: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Self {
+        pairs.into_iter().fold(self, |element, (rel, content)| {
+            let mut child = Element::new(name).attribute("rel", rel);
+            child.text = content.clone();
+            element.child(child)
+        })
+    }
+
+    /// Add a child element for each field that is not known to this library,
+    /// with the value of the field as JSON text.
+    ///
+    /// Fields whose name can not be used as the name of an element are written as
+    /// `<field name="...">` elements instead.
+    fn json_children(self, fields: &Map<String, Value>) -> Self {
+        fields.iter().fold(self, |element, (name, value)| {
+            if is_element_name(name) && name != FIELD_ELEMENT {
+                element.text_child(name, value)
+            } else {
+                let mut child = Element::new(FIELD_ELEMENT).attribute("name", name);
+                child.text = value.to_string();
+                element.child(child)
+            }
+        })
+    }
+
+    /// Get the name of the field this element was written for by [`Element::json_children`].
+    fn field_name(&self) -> &str {
+        match self.get_attribute("name") {
+            Some(name) if self.name == FIELD_ELEMENT => name,
+            _ => &self.name,
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn find_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn text_of(&self, name: &str) -> Option<String> {
+        self.find(name).map(|child| child.text.clone())
+    }
+
+    fn texts_of(&self, name: &str) -> Vec<String> {
+        self.find_all(name)
+            .map(|child| child.text.clone())
+            .collect()
+    }
+
+    fn number_of<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, Error> {
+        self.find(name)
+            .map(|child| {
+                child.text.parse().map_err(|_| {
+                    Error::Xspf(format!(
+                        "expected a number in <{name}>, found {:?}",
+                        child.text
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    fn rels_of(&self, name: &str) -> Vec<(String, String)> {
+        self.find_all(name)
+            .map(|child| {
+                let rel = child.get_attribute("rel").unwrap_or_default().to_string();
+                (rel, child.text.clone())
+            })
+            .collect()
+    }
+
+    /// Collect the children that are not in `known` as fields of an extension.
+    fn json_fields(&self, known: &[&str]) -> Map<String, Value> {
+        self.children
+            .iter()
+            .filter(|child| !known.contains(&child.name.as_str()))
+            .map(|child| (child.field_name().to_string(), child.json()))
+            .collect()
+    }
+
+    /// Convert this element into a JSON value: its text is read as JSON if possible,
+    /// and elements with children become objects.
+    fn json(&self) -> Value {
+        if self.children.is_empty() {
+            return serde_json::from_str(&self.text)
+                .unwrap_or_else(|_| Value::String(self.text.clone()));
+        }
+
+        let mut object = Map::new();
+        for child in &self.children {
+            let value = child.json();
+            match object.get_mut(child.field_name()) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                None => {
+                    object.insert(child.field_name().to_string(), value);
+                }
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn bool_of(&self, name: &str) -> Result<Option<bool>, Error> {
+        match self.find(name).map(|child| child.text.as_str()) {
+            None => Ok(None),
+            Some("false") => Ok(Some(false)),
+            Some("true") => Ok(Some(true)),
+            Some(other) => Err(Error::Xspf(format!(
+                "expected true or false in <{name}>, found {other:?}"
+            ))),
+        }
+    }
+
+    fn extension(&self, application: &str) -> Option<&Element> {
+        self.find_all("extension")
+            .find(|extension| extension.get_attribute("application") == Some(application))
+    }
+}
+
+// --------- JSPF to XML
+
+fn playlist_element(info: &PlaylistInfo) -> Element {
+    let tracks = info
+        .track
+        .iter()
+        .fold(Element::new("trackList"), |list, track| {
+            list.child(track_element(track))
+        });
+
+    let mut element = Element::new("playlist")
+        .attribute("version", "1")
+        .attribute("xmlns", XSPF_NAMESPACE)
+        .optional_child("title", info.title.as_ref())
+        .optional_child("creator", info.creator.as_ref())
+        .optional_child("annotation", info.annotation.as_ref())
+        .optional_child("info", info.info.as_ref())
+        .optional_child("location", info.location.as_ref())
+        .optional_child("identifier", info.identifier.as_ref())
+        .optional_child("image", info.image.as_ref())
+        .optional_child("date", info.date.as_ref())
+        .optional_child("license", info.license.as_ref());
+
+    if !info.attribution.is_empty() {
+        let attribution =
+            info.attribution
+                .iter()
+                .fold(
+                    Element::new("attribution"),
+                    |list, attribution| match attribution {
+                        Attribution::Location(location) => list.text_child("location", location),
+                        Attribution::Identifier(identifier) => {
+                            list.text_child("identifier", identifier)
+                        }
+                    },
+                );
+        element = element.child(attribution);
+    }
+
+    element = element
+        .rel_children(
+            "link",
+            info.link.iter().map(|link| (&link.rel, &link.content)),
+        )
+        .rel_children(
+            "meta",
+            info.meta.iter().map(|meta| (&meta.rel, &meta.content)),
+        );
+
+    if let Some(extension) = &info.extension.musicbrainz {
+        element = element.child(playlist_extension_element(extension));
+    }
+    element = other_extension_elements(element, &info.extension.other);
+
+    element.child(tracks)
+}
+
+fn playlist_extension_element(extension: &MusicBrainzPlaylistExtension) -> Element {
+    let mut element = Element::new("extension")
+        .attribute("application", PLAYLIST_EXTENSION)
+        .optional_child("created_for", extension.created_for.as_ref())
+        .optional_child("creator", extension.creator.as_ref())
+        .text_children("collaborator", extension.collaborators.iter().flatten())
+        .optional_child("copied_from", extension.copied_from.as_ref())
+        .optional_child("copied_from_deleted", extension.copied_from_deleted)
+        .optional_child("public", extension.public)
+        .optional_child("last_modified_at", extension.last_modified_at.as_ref());
+
+    if let Some(additional_metadata) = &extension.additional_metadata {
+        let mut metadata = Element::new("additional_metadata");
+        if let Some(algorithm_metadata) = &additional_metadata.algorithm_metadata {
+            metadata = metadata.child(
+                Element::new("algorithm_metadata")
+                    .optional_child("source_patch", algorithm_metadata.source_patch.as_ref())
+                    .json_children(&algorithm_metadata.other),
+            );
+        }
+        element = element.child(metadata.json_children(&additional_metadata.other));
+    }
+
+    element.json_children(&extension.other)
+}
+
+fn track_element(track: &Track) -> Element {
+    let mut element = Element::new("track")
+        .text_children("location", &track.location)
+        .text_children("identifier", &track.identifier)
+        .optional_child("title", track.title.as_ref())
+        .optional_child("creator", track.creator.as_ref())
+        .optional_child("annotation", track.annotation.as_ref())
+        .optional_child("info", track.info.as_ref())
+        .optional_child("image", track.image.as_ref())
+        .optional_child("album", track.album.as_ref())
+        .optional_child("trackNum", track.track_num)
+        .optional_child("duration", track.duration)
+        .rel_children(
+            "link",
+            track.link.iter().map(|link| (&link.rel, &link.content)),
+        )
+        .rel_children(
+            "meta",
+            track.meta.iter().map(|meta| (&meta.rel, &meta.content)),
+        );
+
+    if let Some(extension) = &track.extension.musicbrainz {
+        element = element.child(
+            Element::new("extension")
+                .attribute("application", TRACK_EXTENSION)
+                .optional_child("added_by", extension.added_by.as_ref())
+                .text_children("artist_mbid", &extension.artist_mbids)
+                .optional_child("added_at", extension.added_at.as_ref())
+                .optional_child("release_identifier", extension.release_identifier.as_ref())
+                .json_children(&extension.other),
+        );
+    }
+
+    other_extension_elements(element, &track.extension.other)
+}
+
+/// Add an `<extension>` element for each extension of another application,
+/// with the extension as JSON text.
+fn other_extension_elements(element: Element, extensions: &Map<String, Value>) -> Element {
+    extensions
+        .iter()
+        .fold(element, |element, (application, value)| {
+            let mut extension = Element::new("extension").attribute("application", application);
+            extension.text = value.to_string();
+            element.child(extension)
+        })
+}
+
+fn write_element(writer: &mut Writer<Vec<u8>>, element: &Element) -> Result<(), Error> {
+    let mut start = BytesStart::new(element.name.as_str());
+    for (name, value) in &element.attributes {
+        start.push_attribute((name.as_str(), value.as_str()));
+    }
+
+    if element.children.is_empty() && element.text.is_empty() {
+        return writer.write_event(Event::Empty(start)).map_err(xml_error);
+    }
+
+    writer.write_event(Event::Start(start)).map_err(xml_error)?;
+    if !element.text.is_empty() {
+        writer
+            .write_event(Event::Text(BytesText::new(&element.text)))
+            .map_err(xml_error)?;
+    }
+    for child in &element.children {
+        write_element(writer, child)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new(element.name.as_str())))
+        .map_err(xml_error)
+}
+
+// --------- XML to JSPF
+
+fn playlist_info(root: &Element) -> Result<PlaylistInfo, Error> {
+    let musicbrainz = root
+        .extension(PLAYLIST_EXTENSION)
+        .map(playlist_extension)
+        .transpose()?;
+
+    let attribution = root
+        .find("attribution")
+        .map(|attribution| {
+            attribution
+                .children
+                .iter()
+                .filter_map(|child| match child.name.as_str() {
+                    "location" => Some(Attribution::Location(child.text.clone())),
+                    "identifier" => Some(Attribution::Identifier(child.text.clone())),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let track = match root.find("trackList") {
+        Some(list) => list
+            .find_all("track")
+            .map(track)
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(PlaylistInfo {
+        title: root.text_of("title"),
+        creator: root.text_of("creator"),
+        annotation: root.text_of("annotation"),
+        info: root.text_of("info"),
+        location: root.text_of("location"),
+        identifier: root.text_of("identifier"),
+        image: root.text_of("image"),
+        date: root.text_of("date"),
+        license: root.text_of("license"),
+        attribution,
+        link: links(root),
+        meta: metas(root),
+        extension: PlaylistExtension {
+            musicbrainz,
+            other: other_extensions(root, PLAYLIST_EXTENSION),
+        },
+        track,
+    })
+}
+
+fn playlist_extension(extension: &Element) -> Result<MusicBrainzPlaylistExtension, Error> {
+    Ok(MusicBrainzPlaylistExtension {
+        created_for: extension.text_of("created_for"),
+        creator: extension.text_of("creator"),
+        collaborators: Some(extension.texts_of("collaborator")).filter(|c| !c.is_empty()),
+        copied_from: extension.text_of("copied_from"),
+        copied_from_deleted: extension.bool_of("copied_from_deleted")?,
+        public: extension.bool_of("public")?,
+        last_modified_at: extension.text_of("last_modified_at"),
+        additional_metadata: extension.find("additional_metadata").map(|metadata| {
+            AdditionalMetadata {
+                algorithm_metadata: metadata.find("algorithm_metadata").map(|algorithm| {
+                    AlgorithmMetadata {
+                        source_patch: algorithm.text_of("source_patch"),
+                        other: algorithm.json_fields(&["source_patch"]),
+                    }
+                }),
+                other: metadata.json_fields(&["algorithm_metadata"]),
+            }
+        }),
+        other: extension.json_fields(&[
+            "created_for",
+            "creator",
+            "collaborator",
+            "copied_from",
+            "copied_from_deleted",
+            "public",
+            "last_modified_at",
+            "additional_metadata",
+        ]),
+    })
+}
+
+fn track(element: &Element) -> Result<Track, Error> {
+    let musicbrainz =
+        element
+            .extension(TRACK_EXTENSION)
+            .map(|extension| MusicBrainzTrackExtension {
+                added_by: extension.text_of("added_by"),
+                artist_mbids: extension.texts_of("artist_mbid"),
+                added_at: extension.text_of("added_at"),
+                release_identifier: extension.text_of("release_identifier"),
+                other: extension.json_fields(&[
+                    "added_by",
+                    "artist_mbid",
+                    "added_at",
+                    "release_identifier",
+                ]),
+            });
+
+    Ok(Track {
+        location: element.texts_of("location"),
+        identifier: element.texts_of("identifier"),
+        title: element.text_of("title"),
+        creator: element.text_of("creator"),
+        annotation: element.text_of("annotation"),
+        info: element.text_of("info"),
+        image: element.text_of("image"),
+        album: element.text_of("album"),
+        track_num: element.number_of("trackNum")?,
+        duration: element.number_of("duration")?,
+        link: links(element),
+        meta: metas(element),
+        extension: TrackExtension {
+            musicbrainz,
+            other: other_extensions(element, TRACK_EXTENSION),
+        },
+    })
+}
+
+fn links(element: &Element) -> Vec<Link> {
+    element
+        .rels_of("link")
+        .into_iter()
+        .map(|(rel, content)| Link { rel, content })
+        .collect()
+}
+
+fn metas(element: &Element) -> Vec<Meta> {
+    element
+        .rels_of("meta")
+        .into_iter()
+        .map(|(rel, content)| Meta { rel, content })
+        .collect()
+}
+
+/// Collect the extensions of applications other than `musicbrainz`, keyed by application.
+fn other_extensions(element: &Element, musicbrainz: &str) -> Map<String, Value> {
+    element
+        .find_all("extension")
+        .filter_map(|extension| {
+            let application = extension.get_attribute("application")?;
+            (application != musicbrainz).then(|| (application.to_string(), extension.json()))
+        })
+        .collect()
+}
+
+/// Parse an XML document into a tree of elements, ignoring namespace prefixes.
+fn parse(xml: &str) -> Result<Element, Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(start) => stack.push(start_element(&start)?),
+            Event::Empty(start) => {
+                let element = start_element(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::End(_) => {
+                let mut element = stack
+                    .pop()
+                    .ok_or_else(|| Error::Xspf("unexpected closing tag".to_string()))?;
+                // Whitespace between child elements is indentation, not text.
+                if !element.children.is_empty() && element.text.trim().is_empty() {
+                    element.text.clear();
+                }
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::Eof => return Err(Error::Xspf("unexpected end of document".to_string())),
+            _ => {}
+        }
+    }
+}
+
+fn start_element(start: &BytesStart) -> Result<Element, Error> {
+    let mut element = Element::new(&String::from_utf8_lossy(start.local_name().as_ref()));
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(xml_error)?;
+        element.attributes.push((
+            String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned(),
+            attribute.unescape_value().map_err(xml_error)?.into_owned(),
+        ));
+    }
+    Ok(element)
+}
+
+/// Check if `name` can be used as the name of an element without a namespace prefix.
+///
+/// This is stricter than the XML specification, which allows more characters.
+fn is_element_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+fn xml_error(error: impl std::fmt::Display) -> Error {
+    Error::Xspf(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// An XSPF document with all fields of the MusicBrainz extensions, as written by [`to_string`].
+    const FULL_XSPF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist version="1" xmlns="http://xspf.org/ns/0/">
+  <title>Weekly Exploration for koen</title>
+  <creator>listenbrainz</creator>
+  <annotation>  Indented first line
+
+  and a second one.
+</annotation>
+  <identifier>https://listenbrainz.org/playlist/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae</identifier>
+  <date>2024-03-04T10:15:30.123456+00:00</date>
+  <extension application="https://musicbrainz.org/doc/jspf#playlist">
+    <created_for>koen</created_for>
+    <creator>listenbrainz</creator>
+    <collaborator>alice</collaborator>
+    <collaborator>bob</collaborator>
+    <copied_from>https://listenbrainz.org/playlist/f316bb0e-8e26-44f8-a802-6d2a3688fc7d</copied_from>
+    <copied_from_deleted>false</copied_from_deleted>
+    <public>true</public>
+    <last_modified_at>2024-03-04T10:15:30.123456+00:00</last_modified_at>
+    <additional_metadata>
+      <algorithm_metadata>
+        <source_patch>weekly-exploration</source_patch>
+      </algorithm_metadata>
+      <expires_at>&quot;2024-03-11T00:00:00+00:00&quot;</expires_at>
+    </additional_metadata>
+  </extension>
+  <extension application="https://example.com/extension">{&quot;key&quot;:&quot;value&quot;}</extension>
+  <trackList>
+    <track>
+      <identifier>https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae</identifier>
+      <title>Airbag</title>
+      <album>OK Computer</album>
+      <trackNum>1</trackNum>
+      <duration>284000</duration>
+      <extension application="https://musicbrainz.org/doc/jspf#track">
+        <added_by>troi-bot</added_by>
+        <artist_mbid>a74b1b7f-71a5-4011-9441-d0b5e4122711</artist_mbid>
+        <added_at>2024-03-04T10:15:30.123456+00:00</added_at>
+        <release_identifier>https://musicbrainz.org/release/0b6b4ba0-d36f-47bd-b4ea-6a5b91842d29</release_identifier>
+        <additional_metadata>{&quot;caa_id&quot;:829521842}</additional_metadata>
+      </extension>
+    </track>
+  </trackList>
+</playlist>"#;
+
+    fn round_trip(playlist: &Playlist) -> Playlist {
+        from_str(&to_string(playlist).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trips_xspf_with_full_musicbrainz_extensions() {
+        let playlist = from_str(FULL_XSPF).unwrap();
+        assert_eq!(to_string(&playlist).unwrap(), FULL_XSPF);
+
+        let extension = playlist.playlist.musicbrainz().unwrap();
+        assert_eq!(extension.created_for.as_deref(), Some("koen"));
+        assert_eq!(
+            extension.collaborators,
+            Some(vec!["alice".to_string(), "bob".to_string()])
+        );
+        assert_eq!(extension.copied_from_deleted, Some(false));
+        assert_eq!(extension.public, Some(true));
+        let metadata = extension.additional_metadata.as_ref().unwrap();
+        assert_eq!(
+            metadata
+                .algorithm_metadata
+                .as_ref()
+                .unwrap()
+                .source_patch
+                .as_deref(),
+            Some("weekly-exploration")
+        );
+        assert_eq!(metadata.other["expires_at"], "2024-03-11T00:00:00+00:00");
+        assert_eq!(
+            playlist.playlist.extension.other["https://example.com/extension"],
+            json!({ "key": "value" })
+        );
+
+        let track = &playlist.playlist.track[0];
+        assert_eq!(track.track_num, Some(1));
+        assert_eq!(track.duration, Some(284000));
+        let extension = track.musicbrainz().unwrap();
+        assert_eq!(
+            extension.artist_mbids,
+            ["a74b1b7f-71a5-4011-9441-d0b5e4122711"]
+        );
+        assert_eq!(extension.other["additional_metadata"]["caa_id"], 829521842);
+    }
+
+    #[test]
+    fn keeps_whitespace_in_text() {
+        let annotation = "  Indented first line\n\n  and a second one.\n";
+        let playlist = from_str(FULL_XSPF).unwrap();
+        assert_eq!(playlist.playlist.annotation.as_deref(), Some(annotation));
+
+        let mut playlist = Playlist::default();
+        playlist.playlist.title = Some(" padded ".to_string());
+        playlist.playlist.annotation = Some(annotation.to_string());
+        playlist.playlist.track.push(Track {
+            title: Some("\ttab".to_string()),
+            annotation: Some("   ".to_string()),
+            ..Track::default()
+        });
+        playlist
+            .playlist
+            .extension
+            .other
+            .insert("https://example.com/extension".to_string(), json!(" text "));
+        assert_eq!(round_trip(&playlist), playlist);
+    }
+
+    #[test]
+    fn ignores_indentation_of_other_documents() {
+        let playlist = from_str(
+            "<playlist version=\"1\">\n\t<title>Mix</title>\n\t<trackList>\n\t\t<track>\n\t\t\t\
+             <title>A</title>\n\t\t</track>\n\t</trackList>\n</playlist>\n",
+        )
+        .unwrap();
+        assert_eq!(playlist.playlist.title.as_deref(), Some("Mix"));
+        assert_eq!(playlist.playlist.track[0].title.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn writes_extension_fields_with_odd_names_as_field_elements() {
+        let odd = ["1x", "a b", "a:b", "<x>", "", "field"];
+        let fields: Map<String, Value> = odd
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), json!(i)))
+            .chain([("ok-name".to_string(), json!("ok"))])
+            .collect();
+
+        let mut playlist = Playlist::default();
+        playlist.playlist.extension.musicbrainz = Some(MusicBrainzPlaylistExtension {
+            additional_metadata: Some(AdditionalMetadata {
+                algorithm_metadata: None,
+                other: fields.clone(),
+            }),
+            other: fields.clone(),
+            ..MusicBrainzPlaylistExtension::default()
+        });
+        playlist.playlist.track.push(Track {
+            extension: TrackExtension {
+                musicbrainz: Some(MusicBrainzTrackExtension {
+                    other: fields,
+                    ..MusicBrainzTrackExtension::default()
+                }),
+                other: Map::new(),
+            },
+            ..Track::default()
+        });
+
+        let xml = to_string(&playlist).unwrap();
+        assert!(xml.contains(r#"<field name="1x">0</field>"#));
+        assert!(xml.contains(r#"<field name="&lt;x&gt;">3</field>"#));
+        assert!(xml.contains(r#"<field name="field">5</field>"#));
+        assert!(xml.contains("<ok-name>"));
+        assert!(!xml.contains("<1x") && !xml.contains("<a b") && !xml.contains("<a:b"));
+
+        assert_eq!(from_str(&xml).unwrap(), playlist);
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_playlists() {
+        assert!(matches!(from_str("<html/>"), Err(Error::Xspf(_))));
+        assert!(matches!(from_str("<playlist>"), Err(Error::Xspf(_))));
+        assert!(matches!(
+            from_str(
+                "<playlist><extension application=\"https://musicbrainz.org/doc/jspf#playlist\">\
+                 <public>yes</public></extension></playlist>"
+            ),
+            Err(Error::Xspf(_))
+        ));
+    }
+}
@@ -1,16 +1,28 @@
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::archive::{ArchivedPlaylist, PlaylistArchive};
 use crate::error::Error;
-use crate::raw::request::{ListenType, Payload, SubmitListens, TrackMetadata};
-use crate::raw::Client;
+use crate::raw::jspf::PlaylistInfo;
+use crate::raw::request::{
+    ListenType, MetadataSubmitManualMapping, MusicService, Payload, PlaylistCreate,
+    PlaylistCreatePlaylist, PlaylistCreatePlaylistExtension, PlaylistCreatePlaylistExtensionInner,
+    PlaylistCreatePlaylistTrack, PlaylistEdit, PlaylistEditPlaylist, PlaylistItemAdd,
+    PlaylistItemAddPlaylist, PlaylistItemDelete, PlaylistItemMove, RecommendationFeedbackDelete,
+    RecommendationFeedbackRating, RecommendationFeedbackSubmit, SubmitListens, TrackMetadata,
+};
+use crate::raw::response::UserListensListen;
+use crate::raw::transport::Transport;
+use crate::raw::{Client, ClientBuilder, Mbid, RetryPolicy, UserListensIter, UserName};
 
 /// Contains a ListenBrainz token and the associated username
 /// for authentication purposes.
 #[derive(Debug)]
-struct Auth {
-    token: String,
-    user: String,
+pub(crate) struct Auth {
+    pub(crate) token: String,
+    pub(crate) user: UserName,
 }
 
 /// An ergonomic ListenBrainz client.
@@ -40,11 +52,29 @@ impl ListenBrainz {
         }
     }
 
+    /// Construct a builder to configure a new ListenBrainz client.
+    /// See [`ListenBrainzBuilder`] for the available settings.
+    pub fn builder() -> ListenBrainzBuilder {
+        ListenBrainzBuilder::new()
+    }
+
     /// Get the API URL of this client.
     pub fn api_url(&self) -> &str {
         self.client.api_url()
     }
 
+    /// Enable or disable rate limit aware throttling.
+    /// See [`Client::set_throttling`] for details.
+    pub fn set_throttling(&mut self, enabled: bool) {
+        self.client.set_throttling(enabled);
+    }
+
+    /// Set the policy for retrying requests that failed because of transient problems.
+    /// See [`RetryPolicy`] for details.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.client.set_retry_policy(retry_policy);
+    }
+
     /// Check if this client is authenticated.
     pub fn is_authenticated(&self) -> bool {
         self.auth.is_some()
@@ -72,14 +102,16 @@ impl ListenBrainz {
     /// See the Errors section of [`Client`] for more info on what errors might occur.
     pub fn authenticate(&mut self, token: &str) -> Result<(), Error> {
         let result = self.client.validate_token(token)?;
-        if result.valid && result.user_name.is_some() {
-            self.auth.replace(Auth {
-                token: token.to_string(),
-                user: result.user_name.unwrap(),
-            });
-            return Ok(());
+        match result.user_name {
+            Some(user) if result.valid => {
+                self.auth.replace(Auth {
+                    token: token.to_string(),
+                    user: UserName::new(user)?,
+                });
+                Ok(())
+            }
+            _ => Err(Error::InvalidToken),
         }
-        Err(Error::InvalidToken)
     }
 
     /// Helper method to submit a listen (either "single" or "playing now").
@@ -123,14 +155,7 @@ impl ListenBrainz {
     /// Otherwise, see the Errors section of [`Client`] for more info on
     /// what errors might occur.
     pub fn listen(&self, artist: &str, track: &str, release: Option<&str>) -> Result<(), Error> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            .try_into()
-            .unwrap();
-
-        self.submit_listen(ListenType::Single, Some(now), artist, track, release)
+        self.submit_listen(ListenType::Single, Some(now()), artist, track, release)
     }
 
     /// Submit a listened track with the given listen time, intended for importing
@@ -166,6 +191,423 @@ impl ListenBrainz {
     ) -> Result<(), Error> {
         self.submit_listen(ListenType::PlayingNow, None, artist, track, release)
     }
+
+    /// Create a new playlist, owned by the authenticated user unless another creator was set,
+    /// and return its MBID.
+    /// See [`PlaylistBuilder`] for details.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_create(&self, playlist: PlaylistBuilder) -> Result<String, Error> {
+        let (token, user) = self
+            .auth
+            .as_ref()
+            .map(|auth| (auth.token.as_str(), auth.user.as_str()))
+            .ok_or(Error::NotAuthenticated)?;
+
+        let create = playlist.with_default_creator(user).build()?;
+
+        self.create_playlist(token, create)
+    }
+
+    /// Helper method to create a playlist with any number of tracks, adding the tracks
+    /// that do not fit in the `create` request afterwards, and return its MBID.
+    fn create_playlist(&self, token: &str, mut create: PlaylistCreate) -> Result<String, Error> {
+        let remaining = split_playlist_create(&mut create);
+
+        let playlist_mbid = Mbid::new(self.client.playlist_create(token, create)?.playlist_mbid)?;
+        for add in remaining {
+            self.client
+                .playlist_item_add(token, &playlist_mbid, None, add)?;
+        }
+
+        Ok(playlist_mbid.into())
+    }
+
+    /// Create a playlist with at most `count` of the recordings that are recommended for the
+    /// authenticated user by collaborative filtering, best first, and return its MBID.
+    ///
+    /// The recordings are added after those already in `playlist`.
+    /// Returns [`None`] if there are no recommendations for the user.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    /// If `count` is 0, returns [`Error::InvalidPlaylist`].
+    pub fn playlist_create_from_recommendations(
+        &self,
+        playlist: PlaylistBuilder,
+        count: u64,
+    ) -> Result<Option<String>, Error> {
+        let user = self
+            .auth
+            .as_ref()
+            .map(|auth| &auth.user)
+            .ok_or(Error::NotAuthenticated)?;
+        if count == 0 {
+            return Err(Error::InvalidPlaylist(
+                "the number of recommendations must not be 0".to_string(),
+            ));
+        }
+
+        let recommendations = self
+            .client
+            .cf_recommendation_user_recording_iter(user)
+            .page_size(count.min(MAX_RECOMMENDATIONS_PER_REQUEST))
+            .take(usize::try_from(count).unwrap_or(usize::MAX))
+            .map(|recommendation| recommendation.map(|r| r.recording_mbid))
+            .collect::<Result<Vec<_>, Error>>()?;
+        if recommendations.is_empty() {
+            return Ok(None);
+        }
+
+        self.playlist_create(playlist.recordings(recommendations))
+            .map(Some)
+    }
+
+    /// Add recordings to a playlist owned by (or shared with) the authenticated user.
+    ///
+    /// The recordings are inserted at `position` (0-based), or appended to the end of the
+    /// playlist if `position` is `None`.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    /// Invalid recording MBIDs are rejected with [`Error::InvalidMbid`] before any request is made.
+    pub fn playlist_add_recordings(
+        &self,
+        playlist_mbid: &str,
+        recording_mbids: &[&str],
+        position: Option<u64>,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+        let playlist_mbid = Mbid::new(playlist_mbid)?;
+
+        for (chunk, position) in playlist_item_chunks(recording_mbids, position)? {
+            self.client
+                .playlist_item_add(token, &playlist_mbid, position, chunk)?;
+        }
+
+        Ok(())
+    }
+
+    /// Move `count` recordings of a playlist, starting with `recording_mbid` at position `from`,
+    /// to position `to` (0-based).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_move_recordings(
+        &self,
+        playlist_mbid: &str,
+        recording_mbid: &str,
+        from: u64,
+        to: u64,
+        count: u64,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client.playlist_item_move(
+            token,
+            &Mbid::new(playlist_mbid)?,
+            PlaylistItemMove {
+                mbid: Mbid::new(recording_mbid)?.into(),
+                from,
+                to,
+                count,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove `count` recordings from a playlist, starting at `position` (0-based).
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_remove_recordings(
+        &self,
+        playlist_mbid: &str,
+        position: u64,
+        count: u64,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client.playlist_item_delete(
+            token,
+            &Mbid::new(playlist_mbid)?,
+            PlaylistItemDelete {
+                index: position,
+                count,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Fetch the current state of a playlist, apply `edit` to it and save the result.
+    fn edit_playlist(
+        &self,
+        playlist_mbid: &str,
+        edit: impl FnOnce(&mut PlaylistEditPlaylist),
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+        let playlist_mbid = Mbid::new(playlist_mbid)?;
+
+        let current = self.client.get_playlist(Some(token), &playlist_mbid)?;
+        let mut playlist = PlaylistEditPlaylist::from(&current.playlist);
+        edit(&mut playlist);
+
+        self.client
+            .playlist_edit(token, &playlist_mbid, PlaylistEdit { playlist })?;
+
+        Ok(())
+    }
+
+    /// Change the title of a playlist.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_set_title(&self, playlist_mbid: &str, title: &str) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.title = Some(title.to_string());
+        })
+    }
+
+    /// Change the annotation (description) of a playlist.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_set_annotation(
+        &self,
+        playlist_mbid: &str,
+        annotation: &str,
+    ) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.annotation = Some(annotation.to_string());
+        })
+    }
+
+    /// Make a playlist public or private.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_set_public(&self, playlist_mbid: &str, public: bool) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            playlist.extension.musicbrainz.public = Some(public);
+        })
+    }
+
+    /// Add a collaborator to a playlist. Does nothing if the user already is a collaborator.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_add_collaborator(
+        &self,
+        playlist_mbid: &str,
+        user_name: &str,
+    ) -> Result<(), Error> {
+        let user_name = UserName::new(user_name)?;
+
+        self.edit_playlist(playlist_mbid, |playlist| {
+            let collaborators = playlist
+                .extension
+                .musicbrainz
+                .collaborators
+                .get_or_insert_with(Vec::new);
+            if !collaborators.iter().any(|c| c == user_name.as_str()) {
+                collaborators.push(user_name.into());
+            }
+        })
+    }
+
+    /// Remove a collaborator from a playlist.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_remove_collaborator(
+        &self,
+        playlist_mbid: &str,
+        user_name: &str,
+    ) -> Result<(), Error> {
+        self.edit_playlist(playlist_mbid, |playlist| {
+            if let Some(collaborators) = &mut playlist.extension.musicbrainz.collaborators {
+                collaborators.retain(|c| c != user_name);
+            }
+        })
+    }
+
+    /// Export a playlist to a streaming service that is linked to the account of the
+    /// authenticated user, and return the URL of the new playlist on the service.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_export(
+        &self,
+        playlist_mbid: &str,
+        service: MusicService,
+    ) -> Result<String, Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let response = self
+            .client
+            .playlist_export(token, &Mbid::new(playlist_mbid)?, service)?;
+
+        Ok(response.external_url)
+    }
+
+    /// Import a playlist from a streaming service that is linked to the account of the
+    /// authenticated user, and return the MBID of the ListenBrainz playlist.
+    ///
+    /// `playlist_id` is the ID of the playlist on the service, as listed by
+    /// [`Client::playlist_import`](crate::raw::Client::playlist_import).
+    /// Tracks that could not be mapped to a MusicBrainz recording are left out.
+    /// If the API did not already save the imported playlist, it is created as a private playlist,
+    /// titled `playlist_id` if the playlist on the service has no title.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn playlist_import(
+        &self,
+        service: MusicService,
+        playlist_id: &str,
+    ) -> Result<String, Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        let imported = self
+            .client
+            .playlist_service_tracks(token, service, playlist_id)?
+            .playlist;
+        if let Some(mbid) = saved_playlist_mbid(&imported) {
+            return Ok(mbid);
+        }
+
+        self.create_playlist(token, playlist_create_from(&imported, playlist_id))
+    }
+
+    /// Copy every playlist created for the authenticated user, such as Weekly Jams and
+    /// Weekly Exploration, that is not in `archive` yet, and return the new copies.
+    ///
+    /// ListenBrainz replaces these playlists regularly, so this keeps them around when run
+    /// periodically. Every copy is recorded in `archive` as soon as it is made, so save the
+    /// archive even if an error is returned. See [`PlaylistArchive`] for an example.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn archive_created_for_playlists(
+        &self,
+        archive: &mut PlaylistArchive,
+    ) -> Result<Vec<ArchivedPlaylist>, Error> {
+        let (token, user) = self
+            .auth
+            .as_ref()
+            .map(|auth| (auth.token.as_str(), &auth.user))
+            .ok_or(Error::NotAuthenticated)?;
+
+        let mut playlists = Vec::new();
+        for playlist in self
+            .client
+            .user_playlists_created_for_iter(Some(token), user)
+        {
+            playlists.push(playlist?.playlist);
+        }
+
+        let mut archived = Vec::new();
+        for playlist in unarchived_playlists(playlists, archive) {
+            let source_mbid = playlist.mbid().unwrap_or_default().to_string();
+            let response = self
+                .client
+                .playlist_copy(token, &Mbid::new(&source_mbid)?)?;
+            archive.insert(&source_mbid, &response.playlist_mbid);
+            archived.push(ArchivedPlaylist {
+                source_mbid,
+                copy_mbid: response.playlist_mbid,
+                title: playlist.title,
+            });
+        }
+
+        Ok(archived)
+    }
+
+    /// Iterate over all listens of a user, newest first.
+    /// See [`UserListensIter`] for details and options.
+    ///
+    /// Returns [`Error::InvalidUserName`] if the user name is not valid.
+    pub fn listens(&self, user_name: &str) -> Result<UserListensIter<'_>, Error> {
+        Ok(self.client.user_listens_iter(&UserName::new(user_name)?))
+    }
+
+    /// Get the listens of a user that are not mapped to a MusicBrainz recording, newest first,
+    /// optionally only those with a timestamp greater than `min_ts` and less than `max_ts`.
+    ///
+    /// Fix them with [`ListenBrainz::submit_manual_mapping`], using their `recording_msid`.
+    pub fn unmapped_listens(
+        &self,
+        user_name: &str,
+        min_ts: Option<i64>,
+        max_ts: Option<i64>,
+    ) -> Result<Vec<UserListensListen>, Error> {
+        let mut listens = self.listens(user_name)?.page_size(MAX_LISTENS_PER_REQUEST);
+        if let Some(min_ts) = min_ts {
+            listens = listens.min_ts(min_ts);
+        }
+        if let Some(max_ts) = max_ts {
+            listens = listens.max_ts(max_ts);
+        }
+
+        let mut unmapped = Vec::new();
+        for listen in listens {
+            let listen = listen?;
+            if listen.track_metadata.mbid_mapping.is_none() {
+                unmapped.push(listen);
+            }
+        }
+
+        Ok(unmapped)
+    }
+
+    /// Rate a recording that was recommended to the authenticated user,
+    /// e.g. by [`ListenBrainz::playlist_create_from_recommendations`].
+    /// Replaces any earlier rating of the recording.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn submit_recommendation_feedback(
+        &self,
+        recording_mbid: &str,
+        rating: RecommendationFeedbackRating,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client.recommendation_feedback_submit(
+            token,
+            RecommendationFeedbackSubmit {
+                recording_mbid,
+                rating,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Delete the rating of a recording that was recommended to the authenticated user.
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn delete_recommendation_feedback(&self, recording_mbid: &str) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client.recommendation_feedback_delete(
+            token,
+            RecommendationFeedbackDelete { recording_mbid },
+        )?;
+
+        Ok(())
+    }
+
+    /// Map the listens of a recording (by MSID) to a MusicBrainz recording,
+    /// e.g. one returned by [`ListenBrainz::unmapped_listens`].
+    ///
+    /// If not authenticated, returns [`Error::NotAuthenticated`].
+    pub fn submit_manual_mapping(
+        &self,
+        recording_msid: &str,
+        recording_mbid: &str,
+    ) -> Result<(), Error> {
+        let token = self.authenticated_token().ok_or(Error::NotAuthenticated)?;
+
+        self.client.metadata_submit_manual_mapping(
+            token,
+            MetadataSubmitManualMapping {
+                recording_msid,
+                recording_mbid,
+            },
+        )?;
+
+        Ok(())
+    }
 }
 
 impl Default for ListenBrainz {
@@ -173,3 +615,1214 @@ impl Default for ListenBrainz {
         Self::new()
     }
 }
+
+/// Get the current time as a UNIX timestamp.
+pub(crate) fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        .try_into()
+        .unwrap()
+}
+
+/// The maximum number of listens that can be fetched in a single request.
+pub(crate) const MAX_LISTENS_PER_REQUEST: u64 = 1000;
+
+/// The maximum number of recommendations that can be fetched in a single request.
+pub(crate) const MAX_RECOMMENDATIONS_PER_REQUEST: u64 = 1000;
+
+/// The maximum number of tracks that can be added to a playlist in a single request.
+const MAX_PLAYLIST_ITEMS_PER_REQUEST: usize = 100;
+
+/// Split recording MBIDs into `item/add` requests of at most [`MAX_PLAYLIST_ITEMS_PER_REQUEST`]
+/// tracks each, along with the position to insert each request at.
+pub(crate) fn playlist_item_chunks(
+    recording_mbids: &[&str],
+    position: Option<u64>,
+) -> Result<Vec<(PlaylistItemAdd, Option<u64>)>, Error> {
+    let tracks = recording_mbids
+        .iter()
+        .map(|mbid| {
+            let mbid = Mbid::new(*mbid)?;
+            Ok(PlaylistCreatePlaylistTrack {
+                identifier: format!("https://musicbrainz.org/recording/{mbid}"),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(tracks
+        .chunks(MAX_PLAYLIST_ITEMS_PER_REQUEST)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let add = PlaylistItemAdd {
+                playlist: PlaylistItemAddPlaylist {
+                    track: chunk.to_vec(),
+                },
+            };
+            let position =
+                position.map(|position| position + (i * MAX_PLAYLIST_ITEMS_PER_REQUEST) as u64);
+            (add, position)
+        })
+        .collect())
+}
+
+/// Get the MBID of an imported playlist if the API already saved it on ListenBrainz,
+/// in which case its identifier is the URL of the ListenBrainz playlist.
+pub(crate) fn saved_playlist_mbid(playlist: &PlaylistInfo) -> Option<String> {
+    let identifier = playlist.identifier.as_deref()?;
+    if !identifier.contains("listenbrainz.org/playlist/") {
+        return None;
+    }
+
+    let mbid = identifier.trim_end_matches('/').rsplit('/').next()?;
+    Mbid::new(mbid).ok().map(String::from)
+}
+
+/// Get the playlists with an MBID that are not in `archive`, oldest first, without duplicates.
+pub(crate) fn unarchived_playlists(
+    playlists: Vec<PlaylistInfo>,
+    archive: &PlaylistArchive,
+) -> Vec<PlaylistInfo> {
+    let mut seen = HashSet::new();
+    let mut playlists: Vec<_> = playlists
+        .into_iter()
+        .filter(|playlist| {
+            playlist
+                .mbid()
+                .is_some_and(|mbid| !archive.contains(mbid) && seen.insert(mbid.to_lowercase()))
+        })
+        .collect();
+    // The API lists the newest playlists first
+    playlists.reverse();
+    playlists
+}
+
+/// Build a request to create a private playlist with the recordings of an imported playlist,
+/// titled `playlist_id` if it has no title.
+pub(crate) fn playlist_create_from(playlist: &PlaylistInfo, playlist_id: &str) -> PlaylistCreate {
+    let track = playlist
+        .track
+        .iter()
+        .filter_map(|track| {
+            track
+                .identifier
+                .iter()
+                .find(|identifier| identifier.contains("musicbrainz.org/recording/"))
+        })
+        .map(|identifier| PlaylistCreatePlaylistTrack {
+            identifier: identifier.clone(),
+        })
+        .collect();
+
+    PlaylistCreate {
+        playlist: PlaylistCreatePlaylist {
+            title: playlist
+                .title
+                .clone()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or_else(|| playlist_id.to_string()),
+            annotation: playlist.annotation.clone(),
+            track,
+            extension: PlaylistCreatePlaylistExtension {
+                musicbrainz: PlaylistCreatePlaylistExtensionInner {
+                    created_for: None,
+                    creator: None,
+                    collaborators: Vec::new(),
+                    copied_from: None,
+                    copied_from_deleted: None,
+                    public: false,
+                    last_modified_at: None,
+                    additional_metadata: None,
+                },
+            },
+        },
+    }
+}
+
+/// Split off the tracks of a new playlist beyond the first [`MAX_PLAYLIST_ITEMS_PER_REQUEST`],
+/// into `item/add` requests that append them after the playlist has been created.
+pub(crate) fn split_playlist_create(create: &mut PlaylistCreate) -> Vec<PlaylistItemAdd> {
+    let tracks = &mut create.playlist.track;
+    if tracks.len() <= MAX_PLAYLIST_ITEMS_PER_REQUEST {
+        return Vec::new();
+    }
+
+    tracks
+        .split_off(MAX_PLAYLIST_ITEMS_PER_REQUEST)
+        .chunks(MAX_PLAYLIST_ITEMS_PER_REQUEST)
+        .map(|chunk| PlaylistItemAdd {
+            playlist: PlaylistItemAddPlaylist {
+                track: chunk.to_vec(),
+            },
+        })
+        .collect()
+}
+
+/// Builder for a new playlist.
+///
+/// Recordings are given by their MBID, either as a string or as an [`Mbid`],
+/// and collaborators by their user name. They are validated when the playlist is built,
+/// and turned into the MusicBrainz URIs that the API expects.
+///
+/// Create the playlist with [`ListenBrainz::playlist_create`], which makes the
+/// authenticated user its creator, or use [`PlaylistBuilder::build`] to get the request
+/// for [`Client::playlist_create`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use listenbrainz::{ListenBrainz, PlaylistBuilder};
+/// let mut client = ListenBrainz::new();
+/// client.authenticate("LISTENBRAINZ TOKEN")?;
+///
+/// let playlist = PlaylistBuilder::new("Favourites")
+///     .annotation("Songs I keep coming back to")
+///     .public(true)
+///     .recording("8f3471b5-7e6a-48da-86a9-c1c07a0f47ae")
+///     .collaborator("bob");
+/// let playlist_mbid = client.playlist_create(playlist)?;
+/// # Ok::<(), listenbrainz::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistBuilder {
+    title: String,
+    annotation: Option<String>,
+    public: bool,
+    creator: Option<String>,
+    collaborators: Vec<String>,
+    recordings: Vec<String>,
+}
+
+impl PlaylistBuilder {
+    /// Start building a private playlist with the given title and no recordings.
+    pub fn new(title: impl ToString) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the annotation (description) of the playlist.
+    pub fn annotation(mut self, annotation: impl ToString) -> Self {
+        self.annotation = Some(annotation.to_string());
+        self
+    }
+
+    /// Make the playlist public or private. Playlists are private by default.
+    pub fn public(mut self, public: bool) -> Self {
+        self.public = public;
+        self
+    }
+
+    /// Set the user name of the creator of the playlist.
+    ///
+    /// [`ListenBrainz::playlist_create`] sets this to the authenticated user if it is not set.
+    pub fn creator(mut self, user_name: impl AsRef<str>) -> Self {
+        self.creator = Some(user_name.as_ref().to_string());
+        self
+    }
+
+    /// Add a collaborator, who may edit the playlist.
+    pub fn collaborator(mut self, user_name: impl AsRef<str>) -> Self {
+        self.collaborators.push(user_name.as_ref().to_string());
+        self
+    }
+
+    /// Add multiple collaborators.
+    pub fn collaborators<I>(mut self, user_names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.collaborators.extend(
+            user_names
+                .into_iter()
+                .map(|user_name| user_name.as_ref().to_string()),
+        );
+        self
+    }
+
+    /// Append a recording to the playlist.
+    pub fn recording(mut self, recording_mbid: impl AsRef<str>) -> Self {
+        self.recordings.push(recording_mbid.as_ref().to_string());
+        self
+    }
+
+    /// Append multiple recordings to the playlist.
+    pub fn recordings<I>(mut self, recording_mbids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.recordings.extend(
+            recording_mbids
+                .into_iter()
+                .map(|mbid| mbid.as_ref().to_string()),
+        );
+        self
+    }
+
+    /// Validate the playlist and build the request to create it.
+    ///
+    /// Returns [`Error::InvalidPlaylist`] if the title is empty, [`Error::InvalidMbid`] for
+    /// an invalid recording MBID and [`Error::InvalidUserName`] for an invalid user name.
+    /// Duplicate collaborators, and the creator as a collaborator, are left out.
+    pub fn build(self) -> Result<PlaylistCreate, Error> {
+        if self.title.trim().is_empty() {
+            return Err(Error::InvalidPlaylist(
+                "the title must not be empty".to_string(),
+            ));
+        }
+
+        let creator = self
+            .creator
+            .map(UserName::new)
+            .transpose()?
+            .map(String::from);
+
+        let mut collaborators: Vec<String> = Vec::new();
+        for user_name in self.collaborators {
+            let user_name = String::from(UserName::new(user_name)?);
+            if Some(&user_name) != creator.as_ref() && !collaborators.contains(&user_name) {
+                collaborators.push(user_name);
+            }
+        }
+
+        let track = self
+            .recordings
+            .into_iter()
+            .map(|mbid| {
+                let mbid = Mbid::new(mbid)?;
+                Ok(PlaylistCreatePlaylistTrack {
+                    identifier: format!("https://musicbrainz.org/recording/{mbid}"),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(PlaylistCreate {
+            playlist: PlaylistCreatePlaylist {
+                title: self.title,
+                annotation: self.annotation,
+                track,
+                extension: PlaylistCreatePlaylistExtension {
+                    musicbrainz: PlaylistCreatePlaylistExtensionInner {
+                        created_for: None,
+                        creator,
+                        collaborators,
+                        copied_from: None,
+                        copied_from_deleted: None,
+                        public: self.public,
+                        last_modified_at: None,
+                        additional_metadata: None,
+                    },
+                },
+            },
+        })
+    }
+
+    /// Set the creator to `user_name` if no creator was set.
+    pub(crate) fn with_default_creator(mut self, user_name: &str) -> Self {
+        self.creator.get_or_insert_with(|| user_name.to_string());
+        self
+    }
+}
+
+/// Builder for a [`ListenBrainz`] client with custom settings.
+///
+/// Construct one with [`ListenBrainz::builder`].
+/// This mirrors [`ClientBuilder`]; see there for details on the settings.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use listenbrainz::ListenBrainz;
+/// let client = ListenBrainz::builder()
+///     .user_agent("my-scrobbler/1.0 (me@example.com)")
+///     .timeout(Duration::from_secs(60))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct ListenBrainzBuilder {
+    client: ClientBuilder,
+}
+
+impl ListenBrainzBuilder {
+    /// Construct a new builder with the default settings.
+    pub fn new() -> Self {
+        Self {
+            client: ClientBuilder::new(),
+        }
+    }
+
+    /// Set the API URL. Defaults to `https://api.listenbrainz.org/1/`.
+    pub fn api_url(self, url: impl ToString) -> Self {
+        self.map(|client| client.api_url(url))
+    }
+
+    /// Set the timeout for establishing a connection.
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        self.map(|client| client.connect_timeout(timeout))
+    }
+
+    /// Set the timeout for every read operation on a connection.
+    pub fn read_timeout(self, timeout: Duration) -> Self {
+        self.map(|client| client.read_timeout(timeout))
+    }
+
+    /// Set the timeout for a request as a whole, from connecting to reading the response.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.map(|client| client.timeout(timeout))
+    }
+
+    /// Send all requests through the HTTP proxy at the given URL.
+    pub fn proxy(self, url: impl ToString) -> Self {
+        self.map(|client| client.proxy(url))
+    }
+
+    /// Set the `User-Agent` header sent with every request.
+    pub fn user_agent(self, user_agent: impl ToString) -> Self {
+        self.map(|client| client.user_agent(user_agent))
+    }
+
+    /// Enable or disable rate limit aware throttling.
+    pub fn throttling(self, enabled: bool) -> Self {
+        self.map(|client| client.throttling(enabled))
+    }
+
+    /// Set the policy for retrying requests that failed because of transient problems.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        self.map(|client| client.retry_policy(retry_policy))
+    }
+
+    /// Enable or disable keeping connections alive between requests.
+    /// See [`ClientBuilder::keep_alive`] for details.
+    ///
+    /// Requires the `keep-alive` feature.
+    #[cfg(feature = "keep-alive")]
+    pub fn keep_alive(self, enabled: bool) -> Self {
+        self.map(|client| client.keep_alive(enabled))
+    }
+
+    /// Perform requests using the given [`Transport`].
+    pub fn transport(self, transport: impl Transport + 'static) -> Self {
+        self.map(|client| client.transport(transport))
+    }
+
+    /// Build the client, which is not authenticated.
+    pub fn build(self) -> Result<ListenBrainz, Error> {
+        Ok(ListenBrainz {
+            client: self.client.build()?,
+            auth: None,
+        })
+    }
+
+    fn map(self, f: impl FnOnce(ClientBuilder) -> ClientBuilder) -> Self {
+        Self {
+            client: f(self.client),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::raw::transport::mock::MockTransport;
+    use crate::raw::transport::stub::StubServer;
+    use crate::raw::transport::{HttpRequest, HttpResponse, Method};
+
+    const PLAYLIST_MBID: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47ae";
+
+    /// A stand-in for the API that serves a Spotify playlist with `tracks` tracks.
+    fn import_server(title: Option<&'static str>, tracks: usize) -> MockTransport {
+        MockTransport::new(move |request: &HttpRequest| {
+            let path = request.url.trim_start_matches("http://localhost/1/");
+            let body = match path {
+                "validate-token" => json!({
+                    "code": 200,
+                    "message": "Token valid.",
+                    "valid": true,
+                    "user_name": "koen",
+                }),
+                "playlist/spotify/spotify-id/tracks" => {
+                    let track: Vec<_> = (0..tracks)
+                        .map(|i| {
+                            json!({
+                                "identifier": [format!(
+                                    "https://musicbrainz.org/recording/00000000-0000-0000-0000-{i:012}"
+                                )],
+                            })
+                        })
+                        .collect();
+                    json!({ "playlist": { "title": title, "track": track } })
+                }
+                "playlist/create" => json!({ "status": "ok", "playlist_mbid": PLAYLIST_MBID }),
+                _ if path == format!("playlist/{PLAYLIST_MBID}/item/add") => {
+                    json!({ "status": "ok" })
+                }
+                _ => panic!("unexpected request to {path}"),
+            };
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+
+    fn client(transport: &MockTransport) -> ListenBrainz {
+        let mut client = ListenBrainz::builder()
+            .api_url("http://localhost/1/")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        client.authenticate("token").unwrap();
+        client
+    }
+
+    /// Get the requests sent to an endpoint, with their JSON body.
+    fn bodies(transport: &MockTransport, path: &str) -> Vec<serde_json::Value> {
+        transport
+            .requests()
+            .iter()
+            .filter(|request| request.url.ends_with(path))
+            .map(|request| serde_json::from_slice(request.body.as_ref().unwrap()).unwrap())
+            .collect()
+    }
+
+    fn track_count(body: &serde_json::Value) -> usize {
+        body["playlist"]["track"].as_array().unwrap().len()
+    }
+
+    #[test]
+    fn imports_playlists_with_more_tracks_than_fit_in_a_request() {
+        let transport = import_server(Some("Imported"), 250);
+        let client = client(&transport);
+
+        let mbid = client
+            .playlist_import(MusicService::Spotify, "spotify-id")
+            .unwrap();
+        assert_eq!(mbid, PLAYLIST_MBID);
+
+        let create = bodies(&transport, "playlist/create");
+        assert_eq!(create.len(), 1);
+        assert_eq!(create[0]["playlist"]["title"], "Imported");
+        assert_eq!(track_count(&create[0]), MAX_PLAYLIST_ITEMS_PER_REQUEST);
+
+        let add = bodies(&transport, "item/add");
+        let added: Vec<_> = add.iter().map(track_count).collect();
+        assert_eq!(added, [100, 50]);
+        assert_eq!(
+            add[1]["playlist"]["track"][49]["identifier"],
+            "https://musicbrainz.org/recording/00000000-0000-0000-0000-000000000249"
+        );
+    }
+
+    #[test]
+    fn imports_untitled_playlists_with_the_playlist_id_as_title() {
+        for title in [None, Some(""), Some("  ")] {
+            let transport = import_server(title, 1);
+            let client = client(&transport);
+
+            client
+                .playlist_import(MusicService::Spotify, "spotify-id")
+                .unwrap();
+
+            let create = bodies(&transport, "playlist/create");
+            assert_eq!(create[0]["playlist"]["title"], "spotify-id");
+            assert!(bodies(&transport, "item/add").is_empty());
+        }
+    }
+
+    /// A stand-in for the API that recommends `recommendations` recordings to the user,
+    /// or has no recommendations (`204 No Content`) if `None`.
+    fn recommendations_server(recommendations: Option<u64>) -> MockTransport {
+        MockTransport::new(move |request: &HttpRequest| {
+            let path = request.url.trim_start_matches("http://localhost/1/");
+            let param = |name: &str| {
+                request
+                    .params
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.parse::<u64>().unwrap())
+            };
+            let body = match path {
+                "validate-token" => json!({
+                    "code": 200,
+                    "message": "Token valid.",
+                    "valid": true,
+                    "user_name": "koen",
+                }),
+                "cf/recommendation/user/koen/recording" => {
+                    let Some(total) = recommendations else {
+                        return Ok(HttpResponse::new(204, ""));
+                    };
+                    let offset = param("offset").unwrap_or(0);
+                    let end = (offset + param("count").unwrap_or(25)).min(total);
+                    let mbids: Vec<_> = (offset..end)
+                        .map(|i| {
+                            json!({
+                                "recording_mbid": format!("00000000-0000-0000-0000-{i:012}"),
+                                "score": 1.0,
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "payload": {
+                            "count": mbids.len(),
+                            "entity": "recording",
+                            "last_updated": 0,
+                            "mbids": mbids,
+                            "offset": offset,
+                            "total_mbid_count": total,
+                            "type": "cf",
+                            "user_name": "koen",
+                        }
+                    })
+                }
+                "playlist/create" => json!({ "status": "ok", "playlist_mbid": PLAYLIST_MBID }),
+                _ => panic!("unexpected request to {path}"),
+            };
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+
+    #[test]
+    fn creates_playlists_from_at_most_count_recommendations() {
+        let transport = recommendations_server(Some(30));
+        let client = client(&transport);
+
+        let mbid = client
+            .playlist_create_from_recommendations(PlaylistBuilder::new("Recommended"), 10)
+            .unwrap();
+        assert_eq!(mbid.as_deref(), Some(PLAYLIST_MBID));
+
+        let create = bodies(&transport, "playlist/create");
+        assert_eq!(track_count(&create[0]), 10);
+    }
+
+    #[test]
+    fn creates_no_playlist_without_recommendations() {
+        let transport = recommendations_server(None);
+        let client = client(&transport);
+
+        let mbid = client
+            .playlist_create_from_recommendations(PlaylistBuilder::new("Recommended"), 10)
+            .unwrap();
+        assert_eq!(mbid, None);
+        assert!(bodies(&transport, "playlist/create").is_empty());
+    }
+
+    #[test]
+    fn rejects_playlists_from_zero_recommendations() {
+        let transport = recommendations_server(Some(30));
+        let client = client(&transport);
+
+        let result = client.playlist_create_from_recommendations(PlaylistBuilder::new("R"), 0);
+        assert!(matches!(result, Err(Error::InvalidPlaylist(_))));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    /// A stand-in for the API with the given playlists created for the user, newest first.
+    fn archive_server(created_for: &'static [&'static str]) -> MockTransport {
+        MockTransport::new(move |request: &HttpRequest| {
+            let path = request.url.trim_start_matches("http://localhost/1/");
+            let param = |name: &str| {
+                request
+                    .params
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.parse::<usize>().unwrap())
+            };
+            let body = match path {
+                "validate-token" => json!({
+                    "code": 200,
+                    "message": "Token valid.",
+                    "valid": true,
+                    "user_name": "koen",
+                }),
+                "user/koen/playlists/createdfor" => {
+                    let offset = param("offset").unwrap_or(0).min(created_for.len());
+                    let end = (offset + param("count").unwrap_or(25)).min(created_for.len());
+                    let playlists: Vec<_> = created_for[offset..end]
+                        .iter()
+                        .map(|mbid| {
+                            json!({ "playlist": {
+                                "identifier": format!("https://listenbrainz.org/playlist/{mbid}"),
+                                "title": format!("Weekly Jams {mbid}"),
+                                "track": [],
+                            }})
+                        })
+                        .collect();
+                    json!({
+                        "count": playlists.len(),
+                        "offset": offset,
+                        "playlist_count": created_for.len(),
+                        "playlists": playlists,
+                    })
+                }
+                _ if path.ends_with("/copy") => {
+                    let source = &path["playlist/".len()..path.len() - "/copy".len()];
+                    let copy = format!("{}{}", &source[..35], "f");
+                    json!({ "status": "ok", "playlist_mbid": copy })
+                }
+                _ => panic!("unexpected request to {path}"),
+            };
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+
+    fn copy_requests(transport: &MockTransport) -> Vec<String> {
+        transport
+            .requests()
+            .iter()
+            .filter(|request| request.url.ends_with("/copy"))
+            .map(|request| {
+                assert_eq!(request.method, Method::Post);
+                request.url.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn archives_created_for_playlists_only_once() {
+        const OLD: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47a0";
+        const NEW: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47a1";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.tsv");
+
+        let transport = archive_server(&[NEW, OLD, NEW]);
+        let mut archive = PlaylistArchive::load(&path).unwrap();
+        let archived = client(&transport)
+            .archive_created_for_playlists(&mut archive)
+            .unwrap();
+        archive.save(&path).unwrap();
+
+        let sources: Vec<_> = archived.iter().map(|p| p.source_mbid.as_str()).collect();
+        assert_eq!(sources, [OLD, NEW]);
+        assert_eq!(
+            archived[0].copy_mbid,
+            "8f3471b5-7e6a-48da-86a9-c1c07a0f47af"
+        );
+        assert_eq!(
+            archived[0].title.as_deref(),
+            Some(format!("Weekly Jams {OLD}").as_str())
+        );
+        assert_eq!(
+            copy_requests(&transport),
+            [
+                format!("http://localhost/1/playlist/{OLD}/copy"),
+                format!("http://localhost/1/playlist/{NEW}/copy"),
+            ]
+        );
+
+        // A second run with the saved archive copies nothing
+        let transport = archive_server(&[NEW, OLD]);
+        let mut archive = PlaylistArchive::load(&path).unwrap();
+        let archived = client(&transport)
+            .archive_created_for_playlists(&mut archive)
+            .unwrap();
+        assert!(archived.is_empty());
+        assert!(copy_requests(&transport).is_empty());
+        assert_eq!(archive.len(), 2);
+    }
+
+    #[test]
+    fn archives_new_created_for_playlists_on_later_runs() {
+        const OLD: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47a0";
+        const NEW: &str = "8f3471b5-7e6a-48da-86a9-c1c07a0f47a1";
+        let mut archive = PlaylistArchive::new();
+        archive.insert(&OLD.to_uppercase(), "8f3471b5-7e6a-48da-86a9-c1c07a0f47af");
+
+        let transport = archive_server(&[NEW, OLD]);
+        let archived = client(&transport)
+            .archive_created_for_playlists(&mut archive)
+            .unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].source_mbid, NEW);
+        assert_eq!(
+            copy_requests(&transport),
+            [format!("http://localhost/1/playlist/{NEW}/copy")]
+        );
+        assert!(archive.contains(NEW));
+    }
+
+    const RECORDING_A: &str = "e97f805a-ab48-4c52-855e-07049142113d";
+    const RECORDING_B: &str = "3ca8fdb1-4ba9-4cb1-a7e4-5a3e1e36b4a3";
+
+    /// A stand-in for the API with a public playlist shared with "alice",
+    /// that accepts every change to it.
+    fn playlist_server() -> MockTransport {
+        MockTransport::new(|request: &HttpRequest| {
+            let path = request.url.trim_start_matches("http://localhost/1/");
+            let body = match path {
+                "validate-token" => json!({
+                    "code": 200,
+                    "message": "Token valid.",
+                    "valid": true,
+                    "user_name": "koen",
+                }),
+                _ if path == format!("playlist/{PLAYLIST_MBID}") => json!({
+                    "playlist": {
+                        "title": "Jams",
+                        "annotation": "Weekly",
+                        "identifier": format!("https://listenbrainz.org/playlist/{PLAYLIST_MBID}"),
+                        "extension": {
+                            "https://musicbrainz.org/doc/jspf#playlist": {
+                                "creator": "koen",
+                                "collaborators": ["alice"],
+                                "public": true,
+                            },
+                        },
+                        "track": [],
+                    },
+                }),
+                _ if path == format!("playlist/edit/{PLAYLIST_MBID}") => json!({ "status": "ok" }),
+                _ if path.starts_with(&format!("playlist/{PLAYLIST_MBID}/item/")) => {
+                    json!({ "status": "ok" })
+                }
+                _ => panic!("unexpected request to {path}"),
+            };
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+
+    /// Get the method, path and JSON body (`null` if none) of the requests sent after
+    /// authenticating.
+    fn changes(transport: &MockTransport) -> Vec<(Method, String, serde_json::Value)> {
+        transport
+            .requests()
+            .into_iter()
+            .skip(1)
+            .map(|request| {
+                let path = request.url.trim_start_matches("http://localhost/1/");
+                let body = request
+                    .body
+                    .map(|body| serde_json::from_slice(&body).unwrap())
+                    .unwrap_or_default();
+                (request.method, path.to_string(), body)
+            })
+            .collect()
+    }
+
+    fn tracks(mbids: &[&str]) -> serde_json::Value {
+        let track: Vec<_> = mbids
+            .iter()
+            .map(
+                |mbid| json!({ "identifier": format!("https://musicbrainz.org/recording/{mbid}") }),
+            )
+            .collect();
+        json!({ "playlist": { "track": track } })
+    }
+
+    #[test]
+    fn adds_recordings_to_playlists() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        client
+            .playlist_add_recordings(PLAYLIST_MBID, &[RECORDING_A, RECORDING_B], None)
+            .unwrap();
+        client
+            .playlist_add_recordings(PLAYLIST_MBID, &[RECORDING_B], Some(3))
+            .unwrap();
+
+        assert_eq!(
+            changes(&transport),
+            [
+                (
+                    Method::Post,
+                    format!("playlist/{PLAYLIST_MBID}/item/add"),
+                    tracks(&[RECORDING_A, RECORDING_B]),
+                ),
+                (
+                    Method::Post,
+                    format!("playlist/{PLAYLIST_MBID}/item/add/3"),
+                    tracks(&[RECORDING_B]),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_many_recordings_to_playlists_in_chunks_at_consecutive_positions() {
+        let transport = playlist_server();
+        let client = client(&transport);
+        let mbids = [RECORDING_A; MAX_PLAYLIST_ITEMS_PER_REQUEST + 1];
+
+        client
+            .playlist_add_recordings(PLAYLIST_MBID, &mbids, Some(5))
+            .unwrap();
+
+        let paths: Vec<_> = changes(&transport)
+            .into_iter()
+            .map(|(_, path, body)| (path, track_count(&body)))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (
+                    format!("playlist/{PLAYLIST_MBID}/item/add/5"),
+                    MAX_PLAYLIST_ITEMS_PER_REQUEST
+                ),
+                (
+                    format!(
+                        "playlist/{PLAYLIST_MBID}/item/add/{}",
+                        5 + MAX_PLAYLIST_ITEMS_PER_REQUEST
+                    ),
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_and_removes_playlist_recordings() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        client
+            .playlist_move_recordings(PLAYLIST_MBID, RECORDING_A, 1, 4, 2)
+            .unwrap();
+        client
+            .playlist_remove_recordings(PLAYLIST_MBID, 2, 3)
+            .unwrap();
+
+        assert_eq!(
+            changes(&transport),
+            [
+                (
+                    Method::Post,
+                    format!("playlist/{PLAYLIST_MBID}/item/move"),
+                    json!({ "mbid": RECORDING_A, "from": 1, "to": 4, "count": 2 }),
+                ),
+                (
+                    Method::Post,
+                    format!("playlist/{PLAYLIST_MBID}/item/delete"),
+                    json!({ "index": 2, "count": 3 }),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_playlist_item_changes_before_sending_them() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        assert!(matches!(
+            client.playlist_add_recordings(PLAYLIST_MBID, &[RECORDING_A, "not an MBID"], None),
+            Err(Error::InvalidMbid(_))
+        ));
+        assert!(matches!(
+            client.playlist_move_recordings("not an MBID", RECORDING_A, 0, 1, 1),
+            Err(Error::InvalidMbid(_))
+        ));
+        assert!(matches!(
+            client.playlist_remove_recordings("not an MBID", 0, 1),
+            Err(Error::InvalidMbid(_))
+        ));
+        assert!(changes(&transport).is_empty());
+
+        let client = ListenBrainz::builder()
+            .api_url("http://localhost/1/")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.playlist_add_recordings(PLAYLIST_MBID, &[RECORDING_A], None),
+            Err(Error::NotAuthenticated)
+        ));
+        assert!(matches!(
+            client.playlist_move_recordings(PLAYLIST_MBID, RECORDING_A, 0, 1, 1),
+            Err(Error::NotAuthenticated)
+        ));
+        assert!(matches!(
+            client.playlist_remove_recordings(PLAYLIST_MBID, 0, 1),
+            Err(Error::NotAuthenticated)
+        ));
+        assert!(changes(&transport).is_empty());
+    }
+
+    /// Get the JSON bodies of the `playlist/edit` requests sent so far.
+    fn edits(transport: &MockTransport) -> Vec<serde_json::Value> {
+        bodies(transport, &format!("playlist/edit/{PLAYLIST_MBID}"))
+    }
+
+    fn edit(
+        title: &str,
+        annotation: &str,
+        public: bool,
+        collaborators: &[&str],
+    ) -> serde_json::Value {
+        json!({
+            "playlist": {
+                "title": title,
+                "annotation": annotation,
+                "extension": {
+                    "https://musicbrainz.org/doc/jspf#playlist": {
+                        "public": public,
+                        "collaborators": collaborators,
+                    },
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn edits_playlists_starting_from_their_current_state() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        client.playlist_set_title(PLAYLIST_MBID, "Jams 2").unwrap();
+
+        assert_eq!(
+            changes(&transport),
+            [
+                (
+                    Method::Get,
+                    format!("playlist/{PLAYLIST_MBID}"),
+                    serde_json::Value::Null,
+                ),
+                (
+                    Method::Post,
+                    format!("playlist/edit/{PLAYLIST_MBID}"),
+                    edit("Jams 2", "Weekly", true, &["alice"]),
+                ),
+            ]
+        );
+        let fetch = &transport.requests()[1];
+        assert!(fetch
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Authorization" && value == "Token token"));
+
+        client
+            .playlist_set_annotation(PLAYLIST_MBID, "Every week")
+            .unwrap();
+        client.playlist_set_public(PLAYLIST_MBID, false).unwrap();
+        assert_eq!(
+            edits(&transport)[1..],
+            [
+                edit("Jams", "Every week", true, &["alice"]),
+                edit("Jams", "Weekly", false, &["alice"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_playlist_collaborators() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        client
+            .playlist_add_collaborator(PLAYLIST_MBID, "bob")
+            .unwrap();
+        client
+            .playlist_add_collaborator(PLAYLIST_MBID, "alice")
+            .unwrap();
+        client
+            .playlist_remove_collaborator(PLAYLIST_MBID, "alice")
+            .unwrap();
+        client
+            .playlist_remove_collaborator(PLAYLIST_MBID, "bob")
+            .unwrap();
+
+        assert_eq!(
+            edits(&transport),
+            [
+                edit("Jams", "Weekly", true, &["alice", "bob"]),
+                edit("Jams", "Weekly", true, &["alice"]),
+                edit("Jams", "Weekly", true, &[]),
+                edit("Jams", "Weekly", true, &["alice"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_playlist_edits_before_sending_them() {
+        let transport = playlist_server();
+        let client = client(&transport);
+
+        assert!(matches!(
+            client.playlist_set_title("not an MBID", "Jams 2"),
+            Err(Error::InvalidMbid(_))
+        ));
+        assert!(matches!(
+            client.playlist_add_collaborator(PLAYLIST_MBID, " "),
+            Err(Error::InvalidUserName(_))
+        ));
+        assert!(changes(&transport).is_empty());
+
+        let client = ListenBrainz::builder()
+            .api_url("http://localhost/1/")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.playlist_set_public(PLAYLIST_MBID, true),
+            Err(Error::NotAuthenticated)
+        ));
+        assert!(matches!(
+            client.playlist_add_collaborator(PLAYLIST_MBID, "bob"),
+            Err(Error::NotAuthenticated)
+        ));
+        assert!(changes(&transport).is_empty());
+    }
+
+    #[test]
+    fn builds_playlist_requests() {
+        let recording = Mbid::new("8f3471b5-7e6a-48da-86a9-c1c07a0f47ae").unwrap();
+        let playlist = PlaylistBuilder::new("Favourites")
+            .annotation("Songs I keep coming back to")
+            .public(true)
+            .creator("koen")
+            .recording(&recording)
+            .recordings(["F316BB0E-8E26-44F8-A802-6D2A3688FC7D"])
+            .collaborator("bob")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(playlist).unwrap(),
+            json!({
+                "playlist": {
+                    "title": "Favourites",
+                    "annotation": "Songs I keep coming back to",
+                    "track": [
+                        { "identifier": "https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae" },
+                        { "identifier": "https://musicbrainz.org/recording/F316BB0E-8E26-44F8-A802-6D2A3688FC7D" },
+                    ],
+                    "extension": {
+                        "https://musicbrainz.org/doc/jspf#playlist": {
+                            "created_for": null,
+                            "creator": "koen",
+                            "collaborators": ["bob"],
+                            "copied_from": null,
+                            "copied_from_deleted": null,
+                            "public": true,
+                            "last_modified_at": null,
+                            "additional_metadata": null,
+                        },
+                    },
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn builds_private_playlists_without_recordings_by_default() {
+        let playlist = PlaylistBuilder::new("Empty").build().unwrap().playlist;
+        assert!(playlist.track.is_empty());
+        assert_eq!(playlist.annotation, None);
+        let extension = playlist.extension.musicbrainz;
+        assert!(!extension.public);
+        assert_eq!(extension.creator, None);
+        assert!(extension.collaborators.is_empty());
+    }
+
+    #[test]
+    fn leaves_out_duplicate_collaborators_and_the_creator() {
+        let playlist = PlaylistBuilder::new("Shared")
+            .creator("koen")
+            .collaborators(["bob", "alice", "bob"])
+            .collaborator("koen")
+            .collaborator("alice")
+            .build()
+            .unwrap();
+        assert_eq!(
+            playlist.playlist.extension.musicbrainz.collaborators,
+            ["bob", "alice"]
+        );
+    }
+
+    #[test]
+    fn sets_the_default_creator_only_if_missing() {
+        let playlist = PlaylistBuilder::new("Mine")
+            .collaborator("koen")
+            .with_default_creator("koen")
+            .build()
+            .unwrap();
+        let extension = playlist.playlist.extension.musicbrainz;
+        assert_eq!(extension.creator.as_deref(), Some("koen"));
+        assert!(extension.collaborators.is_empty());
+
+        let playlist = PlaylistBuilder::new("Theirs")
+            .creator("bob")
+            .with_default_creator("koen")
+            .build()
+            .unwrap();
+        let creator = playlist.playlist.extension.musicbrainz.creator;
+        assert_eq!(creator.as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn rejects_invalid_playlists() {
+        for title in ["", "  \n"] {
+            assert!(matches!(
+                PlaylistBuilder::new(title).build(),
+                Err(Error::InvalidPlaylist(_))
+            ));
+        }
+
+        let error = PlaylistBuilder::new("Mix")
+            .recording("8f3471b5-7e6a-48da-86a9-c1c07a0f47ae")
+            .recording("https://musicbrainz.org/recording/8f3471b5-7e6a-48da-86a9-c1c07a0f47ae")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidMbid(mbid) if mbid.starts_with("https://")));
+
+        let error = PlaylistBuilder::new("Mix")
+            .collaborator("bob")
+            .collaborator("..")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidUserName(user) if user == ".."));
+
+        let error = PlaylistBuilder::new("Mix").creator("").build().unwrap_err();
+        assert!(matches!(error, Error::InvalidUserName(user) if user.is_empty()));
+    }
+
+    #[test]
+    fn builds_clients_with_the_connection_settings() {
+        let server = StubServer::start(
+            200,
+            r#"{"code": 200, "message": "Token valid.", "valid": true, "user_name": "koen"}"#,
+        );
+
+        let mut client = ListenBrainz::builder()
+            .api_url(server.url())
+            .user_agent("my-scrobbler/1.0 (me@example.com)")
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        assert_eq!(client.api_url(), server.url());
+        client.authenticate("token").unwrap();
+        assert_eq!(client.authenticated_user(), Some("koen"));
+
+        let requests = server.received();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].line, "GET /1/validate-token");
+        assert_eq!(
+            requests[0].header("User-Agent"),
+            Some("my-scrobbler/1.0 (me@example.com)")
+        );
+        assert_eq!(requests[0].header("Authorization"), Some("Token token"));
+
+        let error = ListenBrainz::builder()
+            .proxy("not a proxy URL")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, Error::Http(_)));
+
+        // Connections are accepted by the OS, but never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = ListenBrainz::builder()
+            .api_url(format!("http://{}/1/", listener.local_addr().unwrap()))
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        assert!(matches!(client.authenticate("token"), Err(Error::Http(_))));
+    }
+}